@@ -3,20 +3,31 @@
 use anyhow::{anyhow, Context, Result};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use cookie_store::CookieStore;
-use reqwest::blocking::Client;
-use reqwest::header::{CONTENT_TYPE, COOKIE, SET_COOKIE};
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{CONTENT_TYPE, COOKIE, RETRY_AFTER, SET_COOKIE};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::fmt;
 use std::fs;
 use std::io::{BufReader, BufWriter};
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::verbosity::Verbosity;
 
 /// Minimum required API version
 const MIN_API_VERSION: i64 = 14;
 
+/// Default number of attempts for a retried read, including the first one
+const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Starting delay for exponential backoff between retried attempts
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Default per-request timeout, so a hung wiki doesn't stall the helper forever
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Information about a wiki page
 #[derive(Debug, Clone)]
 pub struct PageInfo {
@@ -72,28 +83,246 @@ struct JsonRpcResponse {
     error: Option<JsonRpcError>,
 }
 
+/// JSON-RPC response structure for a batch request, where `id` is needed to
+/// match each response back to the request that produced it
+#[derive(Deserialize)]
+struct JsonRpcBatchResponse {
+    id: u64,
+    result: Option<Value>,
+    error: Option<JsonRpcError>,
+}
+
+/// Some DokuWiki versions send `mtime`/`revision` timestamps as JSON numbers,
+/// others as numeric strings; accept either rather than failing deserialization
+fn deserialize_timestamp<'de, D>(deserializer: D) -> std::result::Result<i64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n.as_i64().ok_or_else(|| Error::custom("expected an integer timestamp")),
+        Value::String(s) => s.parse().map_err(|_| Error::custom(format!("expected a numeric timestamp, got {:?}", s))),
+        // These fields are all `#[serde(default, ...)]` on optional metadata
+        // (size/rev/revision), so an explicit JSON `null` - which some
+        // DokuWiki versions send instead of omitting the field - should fall
+        // back to the same default a missing field gets, not abort the
+        // whole history/list response over one absent number.
+        Value::Null => Ok(0),
+        other => Err(Error::custom(format!("expected a timestamp, got {:?}", other))),
+    }
+}
+
+/// `dokuwiki.getPagelist` entry
+#[derive(Deserialize)]
+struct RawPageListEntry {
+    id: String,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    rev: i64,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    mtime: i64,
+    #[serde(default)]
+    user: String,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    size: i64,
+}
+
+/// `core.getRecentPageChanges` entry
+#[derive(Deserialize)]
+struct RawRecentChangeEntry {
+    id: String,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    revision: i64,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default = "default_revision_type", rename = "type")]
+    revision_type: String,
+}
+
+/// `core.getPageHistory` / `core.getMediaHistory` entry
+#[derive(Deserialize)]
+struct RawHistoryEntry {
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    revision: i64,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    sizechange: i64,
+    #[serde(default = "default_revision_type", rename = "type")]
+    revision_type: String,
+}
+
+/// `core.listMedia` / `core.getRecentMediaChanges` entry (field names for
+/// revision/author differ between the two RPCs, so both are optional here)
+#[derive(Deserialize)]
+struct RawMediaEntry {
+    id: String,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    size: i64,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    rev: i64,
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    revision: i64,
+    #[serde(default)]
+    user: String,
+    #[serde(default)]
+    author: String,
+}
+
+fn default_revision_type() -> String {
+    "E".to_string()
+}
+
 #[derive(Deserialize)]
 struct JsonRpcError {
     code: i64,
     message: String,
 }
 
+/// A transient failure worth retrying: a dropped connection, a timeout, or an
+/// HTTP 429/502/503/504 from the server. Carries the `Retry-After` delay when
+/// the server sent one, so backoff can honor it instead of guessing.
+#[derive(Debug)]
+struct RetryableError {
+    message: String,
+    retry_after: Option<Duration>,
+}
+
+impl fmt::Display for RetryableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for RetryableError {}
+
+/// Pull a `Retry-After` header off a response, interpreted as a number of
+/// seconds (the HTTP-date form is rare enough from a wiki that we skip it)
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// How we authenticate to the wiki's JSON-RPC endpoint
+#[derive(Clone)]
+enum Credentials {
+    /// The original cookie-based flow: `core.login` once, then ride the session cookie
+    SessionLogin,
+    /// HTTP Basic auth on every request (e.g. a reverse-proxy gateway), no session needed
+    Basic { user: String, pass: String },
+    /// A bearer token on every request (e.g. a DokuWiki API token plugin), no session needed
+    Token(String),
+}
+
+impl Credentials {
+    /// Stateless credentials don't need a session cookie or `core.login` dance
+    fn is_stateless(&self) -> bool {
+        !matches!(self, Credentials::SessionLogin)
+    }
+
+    /// Pick credentials from the environment or git config: `DOKUWIKI_TOKEN` wins,
+    /// then `DOKUWIKI_BASIC_USER`/`DOKUWIKI_BASIC_PASS` (or the equivalent git config
+    /// keys `dokuwiki.basicUser`/`dokuwiki.basicPass`), else fall back to session login
+    fn discover() -> Self {
+        use std::env;
+
+        if let Ok(token) = env::var("DOKUWIKI_TOKEN") {
+            crate::redact::register_secret(&token);
+            return Credentials::Token(token);
+        }
+
+        let basic_user = env::var("DOKUWIKI_BASIC_USER").ok().or_else(|| git_config("dokuwiki.basicUser"));
+        let basic_pass = env::var("DOKUWIKI_BASIC_PASS").ok().or_else(|| git_config("dokuwiki.basicPass"));
+        if let (Some(user), Some(pass)) = (basic_user, basic_pass) {
+            crate::redact::register_secret(&pass);
+            return Credentials::Basic { user, pass };
+        }
+
+        Credentials::SessionLogin
+    }
+}
+
+/// Whether an RPC error looks like "this method doesn't exist" rather than a
+/// real failure of the call itself — the shape we expect when the `move`
+/// plugin isn't installed on the target wiki
+pub fn is_rpc_method_unavailable(e: &anyhow::Error) -> bool {
+    let msg = e.to_string();
+    msg.contains("-32601") || msg.to_lowercase().contains("unknown method") || msg.to_lowercase().contains("method not found")
+}
+
+/// HTTP statuses worth retrying: rate limiting and the "server is overloaded
+/// or behind a flaky proxy" trio, as opposed to a real 4xx client error
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 502 | 503 | 504)
+}
+
+/// Add up to 25% random jitter to a backoff delay, so a batch of clients
+/// hitting the same error don't all retry in lockstep
+fn with_jitter(delay: Duration) -> Duration {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+    let jitter_frac = (nanos % 1000) as f64 / 4000.0; // 0..=0.25
+    delay.mul_f64(1.0 + jitter_frac)
+}
+
+/// DokuWiki's authenticated session typically rides on a cookie named
+/// `DokuWiki`, one of the `DW…`-prefixed variants some setups use, or the
+/// generic PHP `PHPSESSID` when sessions aren't namespaced
+fn is_session_cookie_name(name: &str) -> bool {
+    name.eq_ignore_ascii_case("DokuWiki") || name.starts_with("DW") || name == "PHPSESSID"
+}
+
+/// Per-request timeout, overridable via `DOKUWIKI_TIMEOUT` (seconds) for wikis
+/// that are just slow rather than down
+fn request_timeout() -> Duration {
+    std::env::var("DOKUWIKI_TIMEOUT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_REQUEST_TIMEOUT)
+}
+
+fn git_config(key: &str) -> Option<String> {
+    let output = std::process::Command::new("git").args(["config", key]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if value.is_empty() { None } else { Some(value) }
+}
+
 /// DokuWiki JSON-RPC client
+///
+/// Cheap to clone: the underlying HTTP client and cookie jar are reference
+/// counted, so a clone shares the same connection pool and session as the
+/// original instead of re-authenticating. Used to fan requests out across a
+/// worker pool for concurrent reads (see `fast_import`'s parallel fetch phase).
+#[derive(Clone)]
 pub struct DokuWikiClient {
     wiki_url: String,
     rpc_url: String,
     user: String,
+    namespace: Option<String>,
     client: Client,
     cookie_store: Arc<RwLock<CookieStore>>,
     cookie_path: PathBuf,
     has_loaded_cookies: bool,
+    credentials: Credentials,
+    max_retries: u32,
     verbosity: Verbosity,
     request_id: u64,
 }
 
 impl DokuWikiClient {
     /// Create a new client for the given wiki URL
-    pub fn new(wiki_url: &str, user: &str, verbosity: Verbosity) -> Result<Self> {
+    pub fn new(wiki_url: &str, user: &str, namespace: Option<&str>, verbosity: Verbosity) -> Result<Self> {
         let wiki_url = wiki_url.trim_end_matches('/').to_string();
         let rpc_url = format!("{}/lib/exe/jsonrpc.php", wiki_url);
 
@@ -102,17 +331,21 @@ impl DokuWikiClient {
         let mut has_loaded_cookies = false;
         let cookie_store = if let Ok(ref path) = load_path {
             if path.exists() {
-                if let Ok(file) = fs::File::open(path) {
-                    let reader = BufReader::new(file);
-                    match cookie_store::serde::json::load_all(reader) {
-                        Ok(store) => {
-                            has_loaded_cookies = true;
-                            store
+                match fs::read_to_string(path) {
+                    Ok(contents) if is_netscape_cookie_file(&contents) => {
+                        has_loaded_cookies = true;
+                        parse_netscape_cookies(&contents, &rpc_url)
+                    }
+                    Ok(contents) => {
+                        match cookie_store::serde::json::load_all(BufReader::new(contents.as_bytes())) {
+                            Ok(store) => {
+                                has_loaded_cookies = true;
+                                store
+                            }
+                            Err(_) => CookieStore::new(None),
                         }
-                        Err(_) => CookieStore::new(None),
                     }
-                } else {
-                    CookieStore::new(None)
+                    Err(_) => CookieStore::new(None),
                 }
             } else {
                 CookieStore::new(None)
@@ -128,6 +361,10 @@ impl DokuWikiClient {
         let cookie_store = Arc::new(RwLock::new(cookie_store));
 
         let client = Client::builder()
+            .gzip(true)
+            .timeout(request_timeout())
+            .pool_idle_timeout(Some(Duration::from_secs(90)))
+            .pool_max_idle_per_host(4)
             .build()
             .context("Failed to create HTTP client")?;
 
@@ -135,10 +372,16 @@ impl DokuWikiClient {
             wiki_url,
             rpc_url,
             user: user.to_string(),
+            namespace: namespace.map(|ns| ns.to_string()),
             client,
             cookie_store,
             cookie_path,
             has_loaded_cookies,
+            credentials: Credentials::discover(),
+            max_retries: std::env::var("DOKUWIKI_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_MAX_RETRIES),
             verbosity,
             request_id: 1,
         })
@@ -171,6 +414,24 @@ impl DokuWikiClient {
             .join("; ")
     }
 
+    /// Attach whatever the configured `Credentials` need onto an outgoing request:
+    /// the session cookie for the default flow, or a Basic/bearer header for the
+    /// stateless alternatives (which carry auth on every call, not just login)
+    fn apply_credentials(&self, req: reqwest::blocking::RequestBuilder) -> reqwest::blocking::RequestBuilder {
+        match &self.credentials {
+            Credentials::SessionLogin => {
+                let cookie_header = self.get_cookie_header();
+                if cookie_header.is_empty() {
+                    req
+                } else {
+                    req.header(COOKIE, cookie_header)
+                }
+            }
+            Credentials::Basic { user, pass } => req.basic_auth(user, Some(pass)),
+            Credentials::Token(token) => req.bearer_auth(token),
+        }
+    }
+
     /// Store cookies from response
     fn store_cookies(&self, response: &reqwest::blocking::Response) {
         for cookie_header in response.headers().get_all(SET_COOKIE) {
@@ -192,23 +453,30 @@ impl DokuWikiClient {
         };
         self.request_id += 1;
 
-        let cookie_header = self.get_cookie_header();
-
         let mut req = self.client.post(&self.rpc_url)
             .header(CONTENT_TYPE, "application/json");
-        if !cookie_header.is_empty() {
-            req = req.header(COOKIE, cookie_header);
-        }
+        req = self.apply_credentials(req);
 
         let body = serde_json::to_string(&request)?;
-        let response = req
-            .body(body)
-            .send()
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+        let response = req.body(body).send().map_err(|e| {
+            if e.is_timeout() || e.is_connect() {
+                anyhow::Error::new(RetryableError { message: format!("HTTP request failed: {}", e), retry_after: None })
+            } else {
+                anyhow!("HTTP request failed: {}", e)
+            }
+        })?;
 
         self.store_cookies(&response);
 
         let status = response.status();
+        if is_retryable_status(status) {
+            let retry_after = retry_after(&response);
+            let body_text = response.text().unwrap_or_default();
+            return Err(anyhow::Error::new(RetryableError {
+                message: format!("HTTP error {}: {}", status, body_text),
+                retry_after,
+            }));
+        }
         let body_text = response.text().map_err(|e| anyhow!("Failed to read response body: {}", e))?;
 
         if !status.is_success() {
@@ -231,7 +499,10 @@ impl DokuWikiClient {
             Ok(value) => Ok(value),
             Err(e) => {
                 let err_str = e.to_string();
-                if err_str.contains("401") || err_str.contains("Unauthorized") || err_str.contains("not logged in") {
+                let is_auth_error = err_str.contains("401") || err_str.contains("Unauthorized") || err_str.contains("not logged in");
+                // Basic/token auth carries credentials on every request, so a 401 means
+                // they're simply wrong, not that a session expired; retrying won't help.
+                if is_auth_error && !self.credentials.is_stateless() {
                     self.reauthenticate()?;
                     self.call_inner(method, params)
                 } else {
@@ -241,6 +512,192 @@ impl DokuWikiClient {
         }
     }
 
+    /// Like `call`, but retries transient network failures (timeouts, dropped
+    /// connections, HTTP 429/502/503/504) with exponential backoff and jitter,
+    /// up to `max_retries` attempts, honoring a `Retry-After` header when the
+    /// server sends one. Only safe for idempotent reads: never route
+    /// `put_page`/`put_attachment`/`delete_attachment` through this, since
+    /// retrying an ambiguous write failure risks applying it twice.
+    fn call_retrying(&mut self, method: &str, params: Value) -> Result<Value> {
+        let mut attempt = 0u32;
+        let mut delay = INITIAL_RETRY_DELAY;
+        loop {
+            match self.call(method, params.clone()) {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    attempt += 1;
+                    let Some(retryable) = e.downcast_ref::<RetryableError>() else {
+                        return Err(e);
+                    };
+                    if attempt >= self.max_retries {
+                        return Err(e);
+                    }
+                    let wait = retryable.retry_after.unwrap_or_else(|| with_jitter(delay));
+                    self.verbosity.info(&format!(
+                        "{} failed ({}), retrying in {:?} (attempt {}/{})",
+                        method, retryable, wait, attempt, self.max_retries
+                    ));
+                    std::thread::sleep(wait);
+                    delay *= 2;
+                }
+            }
+        }
+    }
+
+    /// Make several JSON-RPC calls in a single HTTP POST using the JSON-RPC 2.0
+    /// batch request form. Returns one `Result` per input call, in the same
+    /// order, so a per-element error doesn't fail the whole batch. Retries the
+    /// whole batch on a transient transport/server failure, same as
+    /// `call_retrying` — every caller of `call_batch` is an idempotent read.
+    pub fn call_batch(&mut self, calls: Vec<(String, Value)>) -> Result<Vec<Result<Value>>> {
+        let mut attempt = 0u32;
+        let mut delay = INITIAL_RETRY_DELAY;
+        loop {
+            match self.call_batch_inner(calls.clone()) {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    attempt += 1;
+                    let Some(retryable) = e.downcast_ref::<RetryableError>() else {
+                        return Err(e);
+                    };
+                    if attempt >= self.max_retries {
+                        return Err(e);
+                    }
+                    let wait = retryable.retry_after.unwrap_or_else(|| with_jitter(delay));
+                    self.verbosity.info(&format!(
+                        "batch call failed ({}), retrying in {:?} (attempt {}/{})",
+                        retryable, wait, attempt, self.max_retries
+                    ));
+                    std::thread::sleep(wait);
+                    delay *= 2;
+                }
+            }
+        }
+    }
+
+    /// Single attempt at `call_batch`, no retry
+    fn call_batch_inner(&mut self, calls: Vec<(String, Value)>) -> Result<Vec<Result<Value>>> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut requests = Vec::with_capacity(calls.len());
+        let mut order = Vec::with_capacity(calls.len());
+        for (method, params) in &calls {
+            let id = self.request_id;
+            self.request_id += 1;
+            order.push(id);
+            requests.push(JsonRpcRequest {
+                jsonrpc: "2.0",
+                method: method.clone(),
+                params: params.clone(),
+                id,
+            });
+        }
+
+        let mut req = self.client.post(&self.rpc_url)
+            .header(CONTENT_TYPE, "application/json");
+        req = self.apply_credentials(req);
+
+        let body = serde_json::to_string(&requests)?;
+        let response = req.body(body).send().map_err(|e| {
+            if e.is_timeout() || e.is_connect() {
+                anyhow::Error::new(RetryableError { message: format!("HTTP request failed: {}", e), retry_after: None })
+            } else {
+                anyhow!("HTTP request failed: {}", e)
+            }
+        })?;
+
+        self.store_cookies(&response);
+
+        let status = response.status();
+        if is_retryable_status(status) {
+            let ra = retry_after(&response);
+            let body_text = response.text().unwrap_or_default();
+            return Err(anyhow::Error::new(RetryableError { message: format!("HTTP error {}: {}", status, body_text), retry_after: ra }));
+        }
+        let body_text = response.text().map_err(|e| anyhow!("Failed to read response body: {}", e))?;
+
+        if !status.is_success() {
+            return Err(anyhow!("HTTP error {}: {}", status, body_text));
+        }
+
+        // A server without batch support typically replies with a single error
+        // object rather than an array; fall back to issuing the calls one by one.
+        let responses: Vec<JsonRpcBatchResponse> = match serde_json::from_str(&body_text) {
+            Ok(responses) => responses,
+            Err(_) => {
+                self.verbosity.debug("Server rejected batch request, falling back to sequential calls");
+                return Ok(calls
+                    .into_iter()
+                    .map(|(method, params)| self.call_retrying(&method, params))
+                    .collect());
+            }
+        };
+
+        let mut by_id: std::collections::HashMap<u64, Result<Value>> = responses
+            .into_iter()
+            .map(|r| {
+                let result = match r.error {
+                    Some(error) => Err(anyhow!("API error {}: {}", error.code, error.message)),
+                    None => r.result.ok_or_else(|| anyhow!("No result in response")),
+                };
+                (r.id, result)
+            })
+            .collect();
+
+        Ok(order
+            .into_iter()
+            .map(|id| {
+                by_id
+                    .remove(&id)
+                    .unwrap_or_else(|| Err(anyhow!("No response for request id {}", id)))
+            })
+            .collect())
+    }
+
+    /// Fetch several pages' content at their given revisions in one round-trip
+    pub fn get_pages_batch(&mut self, ids: &[(&str, i64)]) -> Result<Vec<Result<String>>> {
+        let calls = ids
+            .iter()
+            .map(|(page, rev)| ("core.getPage".to_string(), json!({ "page": page, "rev": rev })))
+            .collect();
+
+        let results = self.call_batch(calls)?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| {
+                r.and_then(|v| {
+                    v.as_str()
+                        .map(|s| s.to_string())
+                        .ok_or_else(|| anyhow!("Expected string from getPage"))
+                })
+            })
+            .collect())
+    }
+
+    /// Fetch several media files' content at their given revisions in one
+    /// round-trip, the `core.getMedia` counterpart to `get_pages_batch`
+    pub fn get_attachments_batch(&mut self, ids: &[(&str, i64)]) -> Result<Vec<Result<Vec<u8>>>> {
+        let calls = ids
+            .iter()
+            .map(|(media, rev)| ("core.getMedia".to_string(), json!({ "media": media, "rev": rev })))
+            .collect();
+
+        let results = self.call_batch(calls)?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| {
+                r.and_then(|v| {
+                    let base64_data = v.as_str().ok_or_else(|| anyhow!("Expected base64 string from getMedia"))?;
+                    BASE64.decode(base64_data).map_err(|e| anyhow!("Failed to decode base64: {}", e))
+                })
+            })
+            .collect())
+    }
+
     /// Re-authenticate after a session expiry
     fn reauthenticate(&mut self) -> Result<()> {
         self.verbosity.info("Session expired, re-authenticating...");
@@ -251,15 +708,31 @@ impl DokuWikiClient {
         *self.cookie_store.write().unwrap() = CookieStore::new(None);
 
         let (user, password) = self.get_credentials()?;
-        self.login(&user, &password)?;
+        match self.login(&user, &password) {
+            Ok(()) => self.approve_credentials(&user, &password),
+            Err(e) => {
+                self.reject_credentials(&user, &password);
+                return Err(e);
+            }
+        }
         self.user = user;
         self.save_cookies()?;
 
         Ok(())
     }
 
+    /// Whether the loaded cookie jar still holds a live DokuWiki session,
+    /// checked up front rather than discovering it's gone from a failed
+    /// request. `iter_unexpired` already treats session cookies (no expiry,
+    /// or expiry `0`) as valid and drops anything whose expiry has passed, so
+    /// a stale session file from days ago is caught here instead of via the
+    /// brittle "does the error text say 401?" check in `call`.
     fn has_cached_session(&self) -> bool {
-        self.has_loaded_cookies
+        if !self.has_loaded_cookies {
+            return false;
+        }
+        let store = self.cookie_store.read().unwrap();
+        store.iter_unexpired().any(|c| is_session_cookie_name(c.name()))
     }
 
     /// Get the wiki host (e.g., "wiki.example.com")
@@ -272,7 +745,11 @@ impl DokuWikiClient {
 
     /// Ensure we're authenticated and API version is sufficient
     pub fn ensure_authenticated(&mut self) -> Result<()> {
-        if self.has_cached_session() {
+        if self.credentials.is_stateless() {
+            // Basic auth and tokens ride along on every request; there's no
+            // `core.login` handshake or session cookie to establish up front.
+            self.verbosity.info("Using pluggable credentials (no session login needed)");
+        } else if self.has_cached_session() {
             self.verbosity.info(&format!("Using cached session for {}", self.user));
             // If cookies were loaded from env var but we're saving to .git/, copy them
             if !self.cookie_path.exists() {
@@ -280,7 +757,13 @@ impl DokuWikiClient {
             }
         } else {
             let (user, password) = self.get_credentials()?;
-            self.login(&user, &password)?;
+            match self.login(&user, &password) {
+                Ok(()) => self.approve_credentials(&user, &password),
+                Err(e) => {
+                    self.reject_credentials(&user, &password);
+                    return Err(e);
+                }
+            }
             self.user = user;
             self.save_cookies()?;
         }
@@ -299,6 +782,22 @@ impl DokuWikiClient {
         Ok(())
     }
 
+    /// Build the `protocol=`/`host=`/`path=`/`username=` attribute block that git's
+    /// credential helper protocol expects on stdin, shared by fill/approve/reject.
+    fn credential_attrs(&self, username: &str) -> Result<String> {
+        let url: url::Url = self.rpc_url.parse()?;
+        let host = url.host_str().unwrap_or("unknown");
+
+        let mut input = format!("protocol=https\nhost={}\n", host);
+        if let Some(ns) = &self.namespace {
+            input.push_str(&format!("path={}\n", ns));
+        }
+        if !username.is_empty() {
+            input.push_str(&format!("username={}\n", username));
+        }
+        Ok(input)
+    }
+
     /// Get credentials using git credential helper or environment
     fn get_credentials(&self) -> Result<(String, String)> {
         use std::env;
@@ -317,10 +816,7 @@ impl DokuWikiClient {
         let url: url::Url = self.rpc_url.parse()?;
         let host = url.host_str().unwrap_or("unknown");
 
-        let mut input = format!("protocol=https\nhost={}\n", host);
-        if !self.user.is_empty() {
-            input.push_str(&format!("username={}\n", self.user));
-        }
+        let mut input = self.credential_attrs(&self.user)?;
         input.push('\n');
 
         let mut child = Command::new("git")
@@ -366,8 +862,46 @@ impl DokuWikiClient {
         Ok((username, password))
     }
 
+    /// Tell git's credential helper a credential worked, so it gets cached/persisted
+    /// (osxkeychain, gnome-keyring, wincred, ...)
+    fn approve_credentials(&self, username: &str, password: &str) {
+        self.run_credential_helper("approve", username, password);
+    }
+
+    /// Tell git's credential helper a cached credential was rejected, so the bad
+    /// secret gets purged instead of being offered again next time
+    fn reject_credentials(&self, username: &str, password: &str) {
+        self.run_credential_helper("reject", username, password);
+    }
+
+    fn run_credential_helper(&self, action: &str, username: &str, password: &str) {
+        use std::process::{Command, Stdio};
+
+        let Ok(mut input) = self.credential_attrs(username) else {
+            return;
+        };
+        input.push_str(&format!("password={}\n\n", password));
+
+        let child = Command::new("git")
+            .args(["credential", action])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn();
+
+        if let Ok(mut child) = child {
+            if let Some(mut stdin) = child.stdin.take() {
+                use std::io::Write;
+                let _ = stdin.write_all(input.as_bytes());
+            }
+            let _ = child.wait();
+        }
+    }
+
     /// Login to the wiki
     fn login(&mut self, user: &str, password: &str) -> Result<()> {
+        crate::redact::register_secret(password);
+
         let result = self.call_inner("core.login", json!({
             "user": user,
             "pass": password
@@ -382,103 +916,76 @@ impl DokuWikiClient {
 
     /// Get API version
     fn get_api_version(&mut self) -> Result<i64> {
-        let result = self.call("core.getAPIVersion", json!({}))?;
+        let result = self.call_retrying("core.getAPIVersion", json!({}))?;
         result.as_i64().ok_or_else(|| anyhow!("Invalid API version response"))
     }
 
     /// Get list of all pages (recursively, all namespaces)
     pub fn get_all_pages(&mut self) -> Result<Vec<PageInfo>> {
-        let result = self.call("dokuwiki.getPagelist", json!({
+        let result = self.call_retrying("dokuwiki.getPagelist", json!({
             "ns": "",
             "opts": { "depth": 0 }
         }))?;
-        parse_page_list(&result)
+        parse_page_list(result)
     }
 
     /// Get list of pages in a namespace
     pub fn get_page_list(&mut self, namespace: &str) -> Result<Vec<PageInfo>> {
-        let result = self.call("dokuwiki.getPagelist", json!({
+        let result = self.call_retrying("dokuwiki.getPagelist", json!({
             "ns": namespace,
             "opts": { "depth": 0 }
         }))?;
-        parse_page_list(&result)
+        parse_page_list(result)
     }
 
     /// Get recent page changes since a given timestamp
     pub fn get_recent_changes(&mut self, since: i64) -> Result<Vec<PageVersion>> {
-        let result = self.call("core.getRecentPageChanges", json!({
+        let result = self.call_retrying("core.getRecentPageChanges", json!({
             "timestamp": since
         }))?;
 
-        let arr = result.as_array().ok_or_else(|| anyhow!("Expected array"))?;
-
-        let mut changes = Vec::new();
-        for item in arr {
-            let page_id = item["id"].as_str().unwrap_or_default().to_string();
-            let version = item["revision"].as_i64().unwrap_or(0);
-            let author = item["author"].as_str().unwrap_or_default().to_string();
-            let summary = item["summary"].as_str().unwrap_or_default().to_string();
-            let revision_type = item["type"].as_str().unwrap_or("E").to_string();
-
-            if !page_id.is_empty() {
-                changes.push(PageVersion {
-                    page_id: Some(page_id),
-                    version,
-                    author,
-                    summary,
-                    size: 0,
-                    revision_type,
-                });
-            }
-        }
-
-        Ok(changes)
+        let entries: Vec<RawRecentChangeEntry> = serde_json::from_value(result)
+            .map_err(|e| anyhow!("Malformed response from core.getRecentPageChanges: {}", e))?;
+
+        Ok(entries
+            .into_iter()
+            .filter(|e| !e.id.is_empty())
+            .map(|e| PageVersion {
+                page_id: Some(e.id),
+                version: e.revision,
+                author: e.author,
+                summary: e.summary,
+                size: 0,
+                revision_type: e.revision_type,
+            })
+            .collect())
     }
 
     /// Get all versions of a page
     pub fn get_page_versions(&mut self, page_id: &str) -> Result<Vec<PageVersion>> {
-        let result = self.call("core.getPageHistory", json!({
+        let result = self.call_retrying("core.getPageHistory", json!({
             "page": page_id
         }))?;
 
-        let arr = result.as_array().ok_or_else(|| anyhow!("Expected array"))?;
-
-        let mut versions = Vec::new();
-        for item in arr {
-            let version = item["revision"].as_i64().unwrap_or(0);
-            let author = item["author"].as_str().unwrap_or_default().to_string();
-            let summary = item["summary"].as_str().unwrap_or_default().to_string();
-            let size = item["sizechange"].as_i64().unwrap_or(0);
-            let revision_type = item["type"].as_str().unwrap_or("E").to_string();
+        let entries: Vec<RawHistoryEntry> = serde_json::from_value(result)
+            .map_err(|e| anyhow!("Malformed response from core.getPageHistory: {}", e))?;
 
-            versions.push(PageVersion {
+        Ok(entries
+            .into_iter()
+            .map(|e| PageVersion {
                 page_id: None,
-                version,
-                author,
-                summary,
-                size,
-                revision_type,
-            });
-        }
-
-        Ok(versions)
-    }
-
-    /// Get page content at a specific version
-    pub fn get_page_version(&mut self, page_id: &str, version: i64) -> Result<String> {
-        let result = self.call("core.getPage", json!({
-            "page": page_id,
-            "rev": version
-        }))?;
-
-        result.as_str()
-            .map(|s| s.to_string())
-            .ok_or_else(|| anyhow!("Expected string from getPage"))
+                version: e.revision,
+                author: e.author,
+                summary: e.summary,
+                size: e.sizechange,
+                revision_type: e.revision_type,
+            })
+            .collect())
     }
 
     /// Get current page content
     pub fn get_page(&mut self, page_id: &str) -> Result<String> {
-        let result = self.call("core.getPage", json!({ "page": page_id }))?;
+        let result = self.call_retrying("core.getPage", json!({ "page": page_id }))?;
 
         result.as_str()
             .map(|s| s.to_string())
@@ -495,106 +1002,95 @@ impl DokuWikiClient {
         Ok(())
     }
 
+    /// Move/rename a page in place via the `move` plugin's RPC, preserving its
+    /// revision history instead of deleting and recreating it. Not every wiki
+    /// has the move plugin installed; callers should fall back to a
+    /// delete-old/put-new pair when the error shows the RPC method itself is
+    /// unavailable (see `is_rpc_method_unavailable`).
+    pub fn move_page(&mut self, old_id: &str, new_id: &str) -> Result<()> {
+        self.call("plugin.move_page", json!({
+            "from": old_id,
+            "to": new_id
+        }))?;
+        Ok(())
+    }
+
+    /// Move/rename a media file via the `move` plugin's RPC; same fallback
+    /// caveat as `move_page`
+    pub fn move_media(&mut self, old_id: &str, new_id: &str) -> Result<()> {
+        self.call("plugin.move_media", json!({
+            "from": old_id,
+            "to": new_id
+        }))?;
+        Ok(())
+    }
+
     /// Get list of all media files in a namespace
     pub fn get_attachments(&mut self, namespace: &str) -> Result<Vec<MediaInfo>> {
-        let result = self.call("core.listMedia", json!({
+        let result = self.call_retrying("core.listMedia", json!({
             "namespace": namespace,
             "depth": 0  // 0 = unlimited depth, list all media recursively
         }))?;
 
-        let arr = result.as_array().ok_or_else(|| anyhow!("Expected array"))?;
-
-        let mut media = Vec::new();
-        for item in arr {
-            let id = item["id"].as_str().unwrap_or_default().to_string();
-            let size = item["size"].as_i64().unwrap_or(0);
-            let revision = item["rev"].as_i64().unwrap_or(0);
-            let author = item["user"].as_str().unwrap_or_default().to_string();
-
-            if !id.is_empty() {
-                media.push(MediaInfo {
-                    id,
-                    size,
-                    revision,
-                    author,
-                });
-            }
-        }
-
-        Ok(media)
+        let entries: Vec<RawMediaEntry> = serde_json::from_value(result)
+            .map_err(|e| anyhow!("Malformed response from core.listMedia: {}", e))?;
+
+        Ok(entries
+            .into_iter()
+            .filter(|e| !e.id.is_empty())
+            .map(|e| MediaInfo {
+                id: e.id,
+                size: e.size,
+                revision: e.rev,
+                author: e.user,
+            })
+            .collect())
     }
 
     /// Get recent media changes since a given timestamp
     pub fn get_recent_media_changes(&mut self, since: i64) -> Result<Vec<MediaInfo>> {
-        let result = self.call("core.getRecentMediaChanges", json!({
+        let result = self.call_retrying("core.getRecentMediaChanges", json!({
             "timestamp": since
         }))?;
 
-        let arr = result.as_array().ok_or_else(|| anyhow!("Expected array"))?;
-
-        let mut media = Vec::new();
-        for item in arr {
-            let id = item["id"].as_str().unwrap_or_default().to_string();
-            let size = item["size"].as_i64().unwrap_or(0);
-            let revision = item["revision"].as_i64().unwrap_or(0);
-            let author = item["author"].as_str().unwrap_or_default().to_string();
-
-            if !id.is_empty() {
-                media.push(MediaInfo {
-                    id,
-                    size,
-                    revision,
-                    author,
-                });
-            }
-        }
-
-        Ok(media)
+        let entries: Vec<RawMediaEntry> = serde_json::from_value(result)
+            .map_err(|e| anyhow!("Malformed response from core.getRecentMediaChanges: {}", e))?;
+
+        Ok(entries
+            .into_iter()
+            .filter(|e| !e.id.is_empty())
+            .map(|e| MediaInfo {
+                id: e.id,
+                size: e.size,
+                revision: e.revision,
+                author: e.author,
+            })
+            .collect())
     }
 
     /// Get all versions of a media file
     pub fn get_media_versions(&mut self, media_id: &str) -> Result<Vec<MediaVersion>> {
-        let result = self.call("core.getMediaHistory", json!({
+        let result = self.call_retrying("core.getMediaHistory", json!({
             "media": media_id
         }))?;
 
-        let arr = result.as_array().ok_or_else(|| anyhow!("Expected array"))?;
-
-        let mut versions = Vec::new();
-        for item in arr {
-            let version = item["revision"].as_i64().unwrap_or(0);
-            let author = item["author"].as_str().unwrap_or_default().to_string();
-            let summary = item["summary"].as_str().unwrap_or_default().to_string();
-            let revision_type = item["type"].as_str().unwrap_or("E").to_string();
-
-            versions.push(MediaVersion {
-                version,
-                author,
-                summary,
-                revision_type,
-            });
-        }
-
-        Ok(versions)
+        let entries: Vec<RawHistoryEntry> = serde_json::from_value(result)
+            .map_err(|e| anyhow!("Malformed response from core.getMediaHistory: {}", e))?;
+
+        Ok(entries
+            .into_iter()
+            .map(|e| MediaVersion {
+                version: e.revision,
+                author: e.author,
+                summary: e.summary,
+                revision_type: e.revision_type,
+            })
+            .collect())
     }
 
     /// Get media file content (current version)
     pub fn get_attachment(&mut self, media_id: &str) -> Result<Vec<u8>> {
-        let result = self.call("core.getMedia", json!({ "media": media_id }))?;
-
-        let base64_data = result.as_str()
-            .ok_or_else(|| anyhow!("Expected base64 string from getMedia"))?;
-
-        BASE64.decode(base64_data)
-            .map_err(|e| anyhow!("Failed to decode base64: {}", e))
-    }
-
-    /// Get media file content at a specific version
-    pub fn get_attachment_version(&mut self, media_id: &str, version: i64) -> Result<Vec<u8>> {
-        let result = self.call("core.getMedia", json!({
-            "media": media_id,
-            "rev": version
-        }))?;
+        let result = self.call_retrying("core.getMedia", json!({ "media": media_id }))?;
 
         let base64_data = result.as_str()
             .ok_or_else(|| anyhow!("Expected base64 string from getMedia"))?;
@@ -645,27 +1141,97 @@ fn get_cookie_load_path() -> Result<PathBuf> {
     get_repo_cookie_path()
 }
 
-fn parse_page_list(result: &Value) -> Result<Vec<PageInfo>> {
-    let arr = result.as_array().ok_or_else(|| anyhow!("Expected array"))?;
+/// Sniff whether a cookie file is the classic Netscape/Mozilla `cookies.txt`
+/// format rather than our usual `cookie_store` JSON format
+fn is_netscape_cookie_file(contents: &str) -> bool {
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        return !(line.starts_with('{') || line.starts_with('['));
+    }
+    false
+}
 
-    let mut pages = Vec::new();
-    for item in arr {
-        let id = item["id"].as_str().unwrap_or_default().to_string();
-        let revision = item["rev"].as_i64().unwrap_or(0);
-        let last_modified = item["mtime"].as_i64().unwrap_or(0);
-        let author = item["user"].as_str().unwrap_or_default().to_string();
-        let size = item["size"].as_i64().unwrap_or(0);
+/// Parse a Netscape/Mozilla `cookies.txt` file (as exported by browsers) into a
+/// `CookieStore`, so a session copied from a logged-in browser can be reused
+fn parse_netscape_cookies(contents: &str, rpc_url: &str) -> CookieStore {
+    use cookie_store::cookie::time::OffsetDateTime;
+    use cookie_store::cookie::Cookie as RawCookie;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let mut store = CookieStore::new(None);
+    let Ok(url) = rpc_url.parse::<url::Url>() else {
+        return store;
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    for raw_line in contents.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
 
-        if !id.is_empty() {
-            pages.push(PageInfo {
-                id,
-                revision,
-                last_modified,
-                author,
-                size,
-            });
+        // A leading "#HttpOnly_" prefix marks an HttpOnly cookie, not a comment
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(rest) => (rest, true),
+            None if line.starts_with('#') => continue,
+            None => (line, false),
+        };
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 7 {
+            continue;
         }
+
+        let domain = fields[0];
+        let path = fields[2];
+        let secure = fields[3].eq_ignore_ascii_case("TRUE");
+        let expires: i64 = fields[4].parse().unwrap_or(0);
+        let name = fields[5];
+        let value = fields[6];
+
+        // expires == 0 means a session cookie that never expires on its own
+        if expires != 0 && expires < now {
+            continue;
+        }
+
+        let mut builder = RawCookie::build((name.to_string(), value.to_string()))
+            .domain(domain.trim_start_matches('.').to_string())
+            .path(path.to_string())
+            .secure(secure)
+            .http_only(http_only);
+
+        if expires != 0 {
+            if let Ok(expiry) = OffsetDateTime::from_unix_timestamp(expires) {
+                builder = builder.expires(expiry);
+            }
+        }
+
+        let _ = store.insert_raw(&builder.build(), &url);
     }
 
-    Ok(pages)
+    store
+}
+
+fn parse_page_list(result: Value) -> Result<Vec<PageInfo>> {
+    let entries: Vec<RawPageListEntry> = serde_json::from_value(result)
+        .map_err(|e| anyhow!("Malformed response from dokuwiki.getPagelist: {}", e))?;
+
+    Ok(entries
+        .into_iter()
+        .filter(|e| !e.id.is_empty())
+        .map(|e| PageInfo {
+            id: e.id,
+            revision: e.rev,
+            last_modified: e.mtime,
+            author: e.user,
+            size: e.size,
+        })
+        .collect())
 }
@@ -4,6 +4,8 @@
 //! we use git commands to find what actually changed and push only those files.
 
 use anyhow::{anyhow, Error, Result};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::io::BufRead;
 use std::process::Command;
 
@@ -12,7 +14,11 @@ use crate::verbosity::Verbosity;
 
 /// Create a detailed error message for push failures
 fn push_error(failed_item: &str, error: Error, pushed: &[String], pending: &[String]) -> Error {
-    let mut msg = format!("Push failed while trying to {}\nError: {}\n", failed_item, error);
+    let mut msg = format!(
+        "Push failed while trying to {}\nError: {}\n",
+        failed_item,
+        crate::redact::redact(&error.to_string())
+    );
 
     if !pushed.is_empty() {
         msg.push_str("\nSuccessfully pushed:\n");
@@ -56,14 +62,114 @@ fn set_last_revision_timestamp(timestamp: i64) {
         .output();
 }
 
+/// A `dokuwiki.pathMap` rule: a compiled regex and its replacement template,
+/// used the way label-tracker's `ChannelPatterns` turns a path into a target
+/// identifier. Only a full-string match is accepted (the regex's match must
+/// span the entire path) so a rule can't silently claim part of a path.
+struct PathMapRule {
+    regex: Regex,
+    replacement: String,
+}
+
+/// Parse `git config dokuwiki.pathMap`: a comma-separated list of
+/// `regex=>replacement` entries, tried in order for each path
+fn load_path_map() -> Vec<PathMapRule> {
+    let Some(raw) = git_config("dokuwiki.pathMap") else {
+        return Vec::new();
+    };
+
+    raw.split(',')
+        .filter_map(|entry| {
+            let (pattern, replacement) = entry.trim().split_once("=>")?;
+            let regex = Regex::new(pattern.trim()).ok()?;
+            Some(PathMapRule { regex, replacement: replacement.trim().to_string() })
+        })
+        .collect()
+}
+
+/// Try each rule against `path` in order; on the first full-string match,
+/// apply the regex substitution (capture groups included) and convert any
+/// remaining `/` to `:`, the same way the default mapping does
+fn apply_path_map(path: &str, rules: &[PathMapRule]) -> Option<String> {
+    for rule in rules {
+        let Some(caps) = rule.regex.captures(path) else { continue };
+        let whole = caps.get(0).unwrap();
+        if whole.start() != 0 || whole.end() != path.len() {
+            continue;
+        }
+
+        let mut expanded = String::new();
+        caps.expand(&rule.replacement, &mut expanded);
+        return Some(expanded.replace('/', ":"));
+    }
+    None
+}
+
+/// Read a single git config value, or `None` if unset/unavailable
+fn git_config(key: &str) -> Option<String> {
+    let output = Command::new("git").args(["config", key]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if value.is_empty() { None } else { Some(value) }
+}
+
+/// Whether `dokuwiki.format=markdown` authoring mode is enabled: pages are
+/// kept as `.md` files and translated to/from DokuWiki syntax at push/import
+fn markdown_format_enabled() -> bool {
+    git_config("dokuwiki.format").as_deref() == Some("markdown")
+}
+
+/// Parse `git config dokuwiki.userMap`: a comma-separated list of
+/// `email=>wikiUsername` entries mapping git commit emails to the name shown
+/// in the pushed summary, the same `key=>value` shape as `dokuwiki.pathMap`
+fn load_user_map() -> HashMap<String, String> {
+    let Some(raw) = git_config("dokuwiki.userMap") else {
+        return HashMap::new();
+    };
+
+    raw.split(',')
+        .filter_map(|entry| {
+            let (email, user) = entry.trim().split_once("=>")?;
+            Some((email.trim().to_string(), user.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Build a change summary that attributes the edit to the commit's author:
+/// the mapped wiki username from `dokuwiki.userMap` if the author's email is
+/// listed there, otherwise `Name <email>`, prefixed onto the commit subject
+fn attribute_summary(author_name: &str, author_email: &str, subject: &str, user_map: &HashMap<String, String>) -> String {
+    let attribution = user_map.get(author_email).cloned().unwrap_or_else(|| {
+        if author_name.is_empty() {
+            author_email.to_string()
+        } else if author_email.is_empty() {
+            author_name.to_string()
+        } else {
+            format!("{} <{}>", author_name, author_email)
+        }
+    });
+
+    if attribution.is_empty() {
+        subject.to_string()
+    } else {
+        format!("{}: {}", attribution, subject)
+    }
+}
+
 /// Convert a file path back to a DokuWiki page ID
-fn path_to_page_id(path: &str, namespace: Option<&str>, extension: &str) -> Option<String> {
+fn path_to_page_id(path: &str, namespace: Option<&str>, extension: &str, path_map: &[PathMapRule]) -> Option<String> {
     // Only handle files with the configured extension
     let suffix = format!(".{}", extension);
-    let path = path.strip_suffix(&suffix)?;
+    let relative = path.strip_suffix(&suffix)?;
+
+    if let Some(id) = apply_path_map(relative, path_map) {
+        return Some(id);
+    }
 
     // Convert path separators to colons
-    let page_id = path.replace('/', ":");
+    let page_id = relative.replace('/', ":");
 
     // Prepend namespace if specified
     if let Some(ns) = namespace {
@@ -79,8 +185,95 @@ fn is_media_file(path: &str, extension: &str) -> bool {
     !path.ends_with(&page_suffix)
 }
 
+/// What a repo path maps to on the wiki side
+enum WikiItem {
+    Page(String),
+    Media(String),
+}
+
+/// Classify a repo path as a wiki page or media file and compute its id
+fn classify_path(path: &str, namespace: Option<&str>, extension: &str, path_map: &[PathMapRule]) -> Option<WikiItem> {
+    if let Some(page_id) = path_to_page_id(path, namespace, extension, path_map) {
+        Some(WikiItem::Page(page_id))
+    } else if is_media_file(path, extension) {
+        path_to_media_id(path, namespace, path_map).map(WikiItem::Media)
+    } else {
+        None
+    }
+}
+
+/// One line of `git diff-tree --name-status -M -C` output
+struct DiffEntry {
+    status: char,
+    /// Source path for a rename/copy (`R`/`C`), `None` otherwise
+    old_path: Option<String>,
+    path: String,
+}
+
+/// Human-readable description(s) of a diff entry, for the pending/pushed
+/// tracking lists. A same-kind rename collapses to a single "move" item; a
+/// cross-kind rename (e.g. a page's extension changed) falls back to
+/// independent delete-old/update-new items, and a copy yields only an
+/// update-new item.
+fn describe_diff_entry(entry: &DiffEntry, namespace: Option<&str>, extension: &str, path_map: &[PathMapRule]) -> Vec<String> {
+    let new_item = classify_path(&entry.path, namespace, extension, path_map);
+    let old_item = entry.old_path.as_deref().and_then(|p| classify_path(p, namespace, extension, path_map));
+
+    if entry.status == 'R' {
+        match (&old_item, &new_item) {
+            (Some(WikiItem::Page(old_id)), Some(WikiItem::Page(new_id))) => {
+                return vec![format!("move page {} to {}", old_id, new_id)];
+            }
+            (Some(WikiItem::Media(old_id)), Some(WikiItem::Media(new_id))) => {
+                return vec![format!("move media {} to {}", old_id, new_id)];
+            }
+            _ => {}
+        }
+    }
+
+    let mut descs = Vec::new();
+    if entry.status == 'D' || entry.status == 'R' {
+        if let Some(item) = &old_item {
+            descs.push(delete_desc(item));
+        }
+    }
+    if entry.status != 'D' {
+        if let Some(item) = &new_item {
+            descs.push(update_desc(item));
+        }
+    }
+    descs
+}
+
+/// Parse `diff-tree --name-status -M -C` output, which is two tab-separated
+/// columns (`status\tpath`) normally but three for a detected rename/copy
+/// (`R100\told\tnew`, `C100\told\tnew`)
+fn parse_diff_entries(output: &str) -> Vec<DiffEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\t').collect();
+            let status = parts.first()?.chars().next()?;
+            match status {
+                'R' | 'C' if parts.len() == 3 => Some(DiffEntry {
+                    status,
+                    old_path: Some(parts[1].to_string()),
+                    path: parts[2].to_string(),
+                }),
+                'R' | 'C' => None,
+                _ if parts.len() == 2 => Some(DiffEntry { status, old_path: None, path: parts[1].to_string() }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
 /// Convert a media file path back to a DokuWiki media ID
-fn path_to_media_id(path: &str, namespace: Option<&str>) -> Option<String> {
+fn path_to_media_id(path: &str, namespace: Option<&str>, path_map: &[PathMapRule]) -> Option<String> {
+    if let Some(id) = apply_path_map(path, path_map) {
+        return Some(id);
+    }
+
     // Convert path separators to colons
     let media_id = path.replace('/', ":");
 
@@ -164,9 +357,15 @@ pub fn process<R: BufRead>(
         ));
     }
 
-    // Check for remote changes before pushing
+    // Check for remote changes before pushing.
     // Use since + 1 because getRecentChanges returns changes >= timestamp,
-    // and we've already imported the change at exactly `since`
+    // and we've already imported the change at exactly `since`.
+    //
+    // Media can't be merged, so any relevant remote media change still
+    // aborts the push outright. Page changes don't: each page we're about to
+    // update gets a three-way merge against its remote edit below, and only
+    // pages whose hunks genuinely conflict block the push.
+    let mut remote_changed_pages: HashSet<String> = HashSet::new();
     if let Some(since) = get_last_revision_timestamp() {
         let changes = client.get_recent_changes(since + 1)?;
 
@@ -182,11 +381,19 @@ pub fn process<R: BufRead>(
         } else {
             changes
         };
+        remote_changed_pages = relevant_changes.into_iter().filter_map(|c| c.page_id).collect();
+
+        let media_changes = client.get_recent_media_changes(since + 1)?;
+        let relevant_media: Vec<_> = if let Some(ns) = namespace {
+            media_changes.into_iter().filter(|m| m.id.starts_with(&format!("{}:", ns))).collect()
+        } else {
+            media_changes
+        };
 
-        if !relevant_changes.is_empty() {
+        if !relevant_media.is_empty() {
             return Err(anyhow!(
-                "Remote has {} new change(s). Please fetch/pull first.",
-                relevant_changes.len()
+                "Remote has {} new media change(s), which cannot be merged. Please fetch/pull first.",
+                relevant_media.len()
             ));
         }
     }
@@ -219,6 +426,20 @@ pub fn process<R: BufRead>(
         verbosity.info(&format!("Pushing {} commit(s)", commits.len()));
     }
 
+    // Rules from `dokuwiki.pathMap` for repos that split several namespaces
+    // out of one tree; empty when unset, in which case every path falls back
+    // to the single `namespace` prefix below.
+    let path_map = load_path_map();
+
+    // In markdown authoring mode, pages are always `.md` files regardless of
+    // the URL's `?ext=` (which then only affects media-vs-page classification
+    // for any other configured extension, which doesn't apply here).
+    let format_markdown = markdown_format_enabled();
+    let extension = if format_markdown { "md" } else { extension };
+
+    // Mapping from commit author email to wiki username, from `dokuwiki.userMap`
+    let user_map = load_user_map();
+
     // Track what we're pushing for error recovery
     let mut pending_items: Vec<String> = Vec::new();
     let mut pushed_items: Vec<String> = Vec::new();
@@ -226,7 +447,7 @@ pub fn process<R: BufRead>(
     // First, collect all items to be pushed
     for commit in &commits {
         let diff_output = Command::new("git")
-            .args(["diff-tree", "--no-commit-id", "--name-status", "-r", commit])
+            .args(["diff-tree", "--no-commit-id", "--name-status", "-M", "-C", "-r", commit])
             .output()?;
 
         if !diff_output.status.success() {
@@ -235,38 +456,8 @@ pub fn process<R: BufRead>(
 
         let changes = std::str::from_utf8(&diff_output.stdout)?;
 
-        for line in changes.lines() {
-            let parts: Vec<&str> = line.splitn(2, '\t').collect();
-            if parts.len() != 2 {
-                continue;
-            }
-
-            let status = parts[0];
-            let path = parts[1];
-
-            let item_desc = if let Some(page_id) = path_to_page_id(path, namespace, extension) {
-                // It's a page file (has the configured extension)
-                match status {
-                    "D" => Some(format!("delete page {}", page_id)),
-                    "A" | "M" => Some(format!("update page {}", page_id)),
-                    _ => None,
-                }
-            } else if is_media_file(path, extension) {
-                // It's a media file (doesn't have the page extension)
-                if let Some(media_id) = path_to_media_id(path, namespace) {
-                    match status {
-                        "D" => Some(format!("delete media {}", media_id)),
-                        "A" | "M" => Some(format!("update media {}", media_id)),
-                        _ => None,
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-
-            if let Some(desc) = item_desc {
+        for entry in parse_diff_entries(changes) {
+            for desc in describe_diff_entry(&entry, namespace, extension, &path_map) {
                 if !pending_items.contains(&desc) {
                     pending_items.push(desc);
                 }
@@ -276,15 +467,23 @@ pub fn process<R: BufRead>(
 
     // Now push each item, tracking progress
     for commit in &commits {
-        // Get commit message
+        // Get the commit's author identity and subject, so the wiki's
+        // revision log attributes the edit to whoever actually committed it
+        // instead of just the RPC account. `%x00` separates the fields since
+        // the subject itself may contain anything but a NUL.
         let msg_output = Command::new("git")
-            .args(["log", "-1", "--format=%s", commit])
+            .args(["log", "-1", "--format=%an%x00%ae%x00%s", commit])
             .output()?;
-        let message = std::str::from_utf8(&msg_output.stdout)?.trim().to_string();
+        let msg_output = std::str::from_utf8(&msg_output.stdout)?.trim_end_matches('\n');
+        let mut fields = msg_output.splitn(3, '\0');
+        let author_name = fields.next().unwrap_or("");
+        let author_email = fields.next().unwrap_or("");
+        let subject = fields.next().unwrap_or("");
+        let message = attribute_summary(author_name, author_email, subject, &user_map);
 
         // Get changed files in this commit
         let diff_output = Command::new("git")
-            .args(["diff-tree", "--no-commit-id", "--name-status", "-r", commit])
+            .args(["diff-tree", "--no-commit-id", "--name-status", "-M", "-C", "-r", commit])
             .output()?;
 
         if !diff_output.status.success() {
@@ -293,111 +492,46 @@ pub fn process<R: BufRead>(
 
         let changes = std::str::from_utf8(&diff_output.stdout)?;
 
-        for line in changes.lines() {
-            let parts: Vec<&str> = line.splitn(2, '\t').collect();
-            if parts.len() != 2 {
-                continue;
-            }
-
-            let status = parts[0];
-            let path = parts[1];
-
-            // Check if it's a page (has the configured extension)
-            if let Some(page_id) = path_to_page_id(path, namespace, extension) {
-                let item_desc = match status {
-                    "D" => format!("delete page {}", page_id),
-                    "A" | "M" => format!("update page {}", page_id),
-                    _ => continue,
-                };
-
-                if dry_run {
-                    let action = match status {
-                        "D" => "Would delete",
-                        "A" | "M" => "Would update",
-                        _ => continue,
-                    };
-                    eprintln!("  {} page {}", action, page_id);
-                } else {
-                    let result = match status {
-                        "D" => {
-                            verbosity.info(&format!("  Deleting page {}...", page_id));
-                            client.put_page(&page_id, "", &format!("Deleted: {}", message))
-                        }
-                        "A" | "M" => {
-                            let content_output = Command::new("git")
-                                .args(["show", &format!("{}:{}", commit, path)])
-                                .output()?;
-
-                            if content_output.status.success() {
-                                let content = String::from_utf8_lossy(&content_output.stdout);
-                                verbosity.info(&format!("  Updating page {}...", page_id));
-                                client.put_page(&page_id, &content, &message)
-                            } else {
-                                continue;
-                            }
-                        }
-                        _ => continue,
-                    };
-
-                    if let Err(e) = result {
-                        return Err(push_error(&item_desc, e, &pushed_items, &pending_items));
+        for entry in parse_diff_entries(changes) {
+            let new_item = classify_path(&entry.path, namespace, extension, &path_map);
+            let old_item = entry.old_path.as_deref().and_then(|p| classify_path(p, namespace, extension, &path_map));
+
+            // A same-kind rename is a real move: tell DokuWiki to move the
+            // page/media in place so its revision history follows it, falling
+            // back to delete-old+put-new if the move plugin is missing.
+            if entry.status == 'R' {
+                match (&old_item, &new_item) {
+                    (Some(WikiItem::Page(old_id)), Some(WikiItem::Page(new_id))) => {
+                        push_page_move(
+                            client, verbosity, dry_run, old_id, new_id, commit, &entry.path, &message,
+                            format_markdown, &mut remote_changed_pages, &mut pushed_items, &mut pending_items,
+                        )?;
+                        continue;
                     }
+                    (Some(WikiItem::Media(old_id)), Some(WikiItem::Media(new_id))) => {
+                        push_media_move(
+                            client, verbosity, dry_run, old_id, new_id, commit, &entry.path,
+                            &mut pushed_items, &mut pending_items,
+                        )?;
+                        continue;
+                    }
+                    _ => {}
                 }
+            }
 
-                // Move from pending to pushed
-                pending_items.retain(|x| x != &item_desc);
-                if !pushed_items.contains(&item_desc) {
-                    pushed_items.push(item_desc);
+            // Everything else (plain add/modify/delete, a copy, or a
+            // cross-kind rename) is handled as independent delete/update items.
+            if entry.status == 'D' || entry.status == 'R' {
+                if let Some(item) = &old_item {
+                    push_delete(client, verbosity, dry_run, item, &message, &mut pushed_items, &mut pending_items)?;
                 }
             }
-            // Check if it's a media file (doesn't have the page extension)
-            else if is_media_file(path, extension) {
-                let Some(media_id) = path_to_media_id(path, namespace) else {
-                    continue;
-                };
-                let item_desc = match status {
-                    "D" => format!("delete media {}", media_id),
-                    "A" | "M" => format!("update media {}", media_id),
-                    _ => continue,
-                };
-
-                if dry_run {
-                    let action = match status {
-                        "D" => "Would delete",
-                        "A" | "M" => "Would update",
-                        _ => continue,
-                    };
-                    eprintln!("  {} media {}", action, media_id);
-                } else {
-                    let result = match status {
-                        "D" => {
-                            verbosity.info(&format!("  Deleting media {}...", media_id));
-                            client.delete_attachment(&media_id)
-                        }
-                        "A" | "M" => {
-                            let content_output = Command::new("git")
-                                .args(["show", &format!("{}:{}", commit, path)])
-                                .output()?;
-
-                            if content_output.status.success() {
-                                verbosity.info(&format!("  Updating media {}...", media_id));
-                                client.put_attachment(&media_id, &content_output.stdout, true)
-                            } else {
-                                continue;
-                            }
-                        }
-                        _ => continue,
-                    };
-
-                    if let Err(e) = result {
-                        return Err(push_error(&item_desc, e, &pushed_items, &pending_items));
-                    }
-                }
-
-                // Move from pending to pushed
-                pending_items.retain(|x| x != &item_desc);
-                if !pushed_items.contains(&item_desc) {
-                    pushed_items.push(item_desc);
+            if entry.status != 'D' {
+                if let Some(item) = &new_item {
+                    push_update(
+                        client, verbosity, dry_run, item, commit, &entry.path, &message,
+                        format_markdown, &mut remote_changed_pages, &mut pushed_items, &mut pending_items,
+                    )?;
                 }
             }
         }
@@ -416,3 +550,255 @@ pub fn process<R: BufRead>(
 
     Ok(Some(target_ref))
 }
+
+fn kind_and_id(item: &WikiItem) -> (&'static str, &str) {
+    match item {
+        WikiItem::Page(id) => ("page", id),
+        WikiItem::Media(id) => ("media", id),
+    }
+}
+
+fn delete_desc(item: &WikiItem) -> String {
+    let (kind, id) = kind_and_id(item);
+    format!("delete {} {}", kind, id)
+}
+
+fn update_desc(item: &WikiItem) -> String {
+    let (kind, id) = kind_and_id(item);
+    format!("update {} {}", kind, id)
+}
+
+/// Move a pending item's description to the pushed list once it succeeds
+fn mark_pushed(desc: &str, pushed_items: &mut Vec<String>, pending_items: &mut Vec<String>) {
+    pending_items.retain(|x| x != desc);
+    if !pushed_items.iter().any(|x| x == desc) {
+        pushed_items.push(desc.to_string());
+    }
+}
+
+/// Delete a page or media file that was removed in this commit
+fn push_delete(
+    client: &mut DokuWikiClient,
+    verbosity: Verbosity,
+    dry_run: bool,
+    item: &WikiItem,
+    message: &str,
+    pushed_items: &mut Vec<String>,
+    pending_items: &mut Vec<String>,
+) -> Result<()> {
+    let (kind, id) = kind_and_id(item);
+    let desc = delete_desc(item);
+
+    if dry_run {
+        eprintln!("  Would delete {} {}", kind, id);
+        return Ok(());
+    }
+
+    let result = match item {
+        WikiItem::Page(id) => {
+            verbosity.info(&format!("  Deleting page {}...", id));
+            client.put_page(id, "", &format!("Deleted: {}", message))
+        }
+        WikiItem::Media(id) => {
+            verbosity.info(&format!("  Deleting media {}...", id));
+            client.delete_attachment(id)
+        }
+    };
+
+    if let Err(e) = result {
+        return Err(push_error(&desc, e, pushed_items, pending_items));
+    }
+
+    mark_pushed(&desc, pushed_items, pending_items);
+    Ok(())
+}
+
+/// Add or update a page or media file at its current content in `commit`
+#[allow(clippy::too_many_arguments)]
+fn push_update(
+    client: &mut DokuWikiClient,
+    verbosity: Verbosity,
+    dry_run: bool,
+    item: &WikiItem,
+    commit: &str,
+    path: &str,
+    message: &str,
+    format_markdown: bool,
+    remote_changed_pages: &mut HashSet<String>,
+    pushed_items: &mut Vec<String>,
+    pending_items: &mut Vec<String>,
+) -> Result<()> {
+    let (kind, id) = kind_and_id(item);
+    let desc = update_desc(item);
+
+    if dry_run {
+        eprintln!("  Would update {} {}", kind, id);
+        return Ok(());
+    }
+
+    let content_output = Command::new("git")
+        .args(["show", &format!("{}:{}", commit, path)])
+        .output()?;
+    if !content_output.status.success() {
+        return Ok(());
+    }
+
+    let result = match item {
+        WikiItem::Page(id) => {
+            let content = String::from_utf8_lossy(&content_output.stdout);
+            let local = if format_markdown { crate::markdown::to_dokuwiki(&content) } else { content.to_string() };
+
+            if remote_changed_pages.remove(id) {
+                verbosity.info(&format!("  Remote changes detected for page {}, merging...", id));
+                merge_and_push(client, id, path, &local, message)
+            } else {
+                verbosity.info(&format!("  Updating page {}...", id));
+                client.put_page(id, &local, message)
+            }
+        }
+        WikiItem::Media(id) => {
+            verbosity.info(&format!("  Updating media {}...", id));
+            client.put_attachment(id, &content_output.stdout, true)
+        }
+    };
+
+    if let Err(e) = result {
+        return Err(push_error(&desc, e, pushed_items, pending_items));
+    }
+
+    mark_pushed(&desc, pushed_items, pending_items);
+    Ok(())
+}
+
+/// Three-way merge `local` (the content we're about to push) against the
+/// wiki's current text, using the last-synced git copy at `path` (on
+/// `origin/main`) as the common ancestor. Pushes the merge on success;
+/// on conflict, writes conflict markers into the working tree at `path`
+/// instead, for the user to resolve by hand.
+fn merge_and_push(client: &mut DokuWikiClient, id: &str, path: &str, local: &str, message: &str) -> Result<()> {
+    let base_output = Command::new("git")
+        .args(["show", &format!("origin/main:{}", path)])
+        .output()?;
+    let base = if base_output.status.success() {
+        String::from_utf8_lossy(&base_output.stdout).to_string()
+    } else {
+        String::new()
+    };
+
+    let remote = client.get_page(id)?;
+
+    match crate::merge::merge3(&base, local, &remote) {
+        crate::merge::MergeResult::Clean(merged) => client.put_page(id, &merged, message),
+        crate::merge::MergeResult::Conflict(marked) => {
+            std::fs::write(path, marked)?;
+            Err(anyhow!(
+                "page {} has conflicting remote changes; conflict markers written to {} for manual resolution",
+                id, path
+            ))
+        }
+    }
+}
+
+/// Move a renamed page, preferring the `move` plugin's native rename (which
+/// preserves revision history) and falling back to delete-old+put-new if the
+/// plugin isn't installed on the target wiki
+#[allow(clippy::too_many_arguments)]
+fn push_page_move(
+    client: &mut DokuWikiClient,
+    verbosity: Verbosity,
+    dry_run: bool,
+    old_id: &str,
+    new_id: &str,
+    commit: &str,
+    new_path: &str,
+    message: &str,
+    format_markdown: bool,
+    remote_changed_pages: &mut HashSet<String>,
+    pushed_items: &mut Vec<String>,
+    pending_items: &mut Vec<String>,
+) -> Result<()> {
+    let desc = format!("move page {} to {}", old_id, new_id);
+
+    if dry_run {
+        eprintln!("  Would move page {} to {}", old_id, new_id);
+        return Ok(());
+    }
+
+    // Renames aren't mergeable: if the remote moved or edited either id since
+    // our last import, bail out the same way a media conflict does rather
+    // than guessing which side should win.
+    if remote_changed_pages.contains(old_id) || remote_changed_pages.contains(new_id) {
+        return Err(push_error(
+            &desc,
+            anyhow!("remote has changes to {} or {} since the last import; please fetch/pull first", old_id, new_id),
+            pushed_items,
+            pending_items,
+        ));
+    }
+
+    verbosity.info(&format!("  Moving page {} to {}...", old_id, new_id));
+    match client.move_page(old_id, new_id) {
+        Ok(()) => {}
+        Err(e) if crate::dokuwiki::is_rpc_method_unavailable(&e) => {
+            verbosity.info("  move plugin not available, falling back to delete+recreate");
+            client.put_page(old_id, "", &format!("Deleted: {}", message))?;
+            let content_output = Command::new("git")
+                .args(["show", &format!("{}:{}", commit, new_path)])
+                .output()?;
+            let content = if content_output.status.success() {
+                String::from_utf8_lossy(&content_output.stdout).to_string()
+            } else {
+                String::new()
+            };
+            let content = if format_markdown { crate::markdown::to_dokuwiki(&content) } else { content };
+            if let Err(e) = client.put_page(new_id, &content, message) {
+                return Err(push_error(&desc, e, pushed_items, pending_items));
+            }
+        }
+        Err(e) => return Err(push_error(&desc, e, pushed_items, pending_items)),
+    }
+
+    mark_pushed(&desc, pushed_items, pending_items);
+    Ok(())
+}
+
+/// Move a renamed media file, same fallback behavior as `push_page_move`
+fn push_media_move(
+    client: &mut DokuWikiClient,
+    verbosity: Verbosity,
+    dry_run: bool,
+    old_id: &str,
+    new_id: &str,
+    commit: &str,
+    new_path: &str,
+    pushed_items: &mut Vec<String>,
+    pending_items: &mut Vec<String>,
+) -> Result<()> {
+    let desc = format!("move media {} to {}", old_id, new_id);
+
+    if dry_run {
+        eprintln!("  Would move media {} to {}", old_id, new_id);
+        return Ok(());
+    }
+
+    verbosity.info(&format!("  Moving media {} to {}...", old_id, new_id));
+    match client.move_media(old_id, new_id) {
+        Ok(()) => {}
+        Err(e) if crate::dokuwiki::is_rpc_method_unavailable(&e) => {
+            verbosity.info("  move plugin not available, falling back to delete+recreate");
+            client.delete_attachment(old_id)?;
+            let content_output = Command::new("git")
+                .args(["show", &format!("{}:{}", commit, new_path)])
+                .output()?;
+            if content_output.status.success() {
+                if let Err(e) = client.put_attachment(new_id, &content_output.stdout, true) {
+                    return Err(push_error(&desc, e, pushed_items, pending_items));
+                }
+            }
+        }
+        Err(e) => return Err(push_error(&desc, e, pushed_items, pending_items)),
+    }
+
+    mark_pushed(&desc, pushed_items, pending_items);
+    Ok(())
+}
@@ -1,12 +1,221 @@
 //! Generate git fast-import stream from DokuWiki history
 
 use anyhow::Result;
-use std::collections::HashMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use crate::dokuwiki::DokuWikiClient;
 use crate::verbosity::Verbosity;
 
+/// Number of concurrent connections used to fetch revision content; each
+/// fetch is a blocking HTTP round-trip, so this is sized for network
+/// concurrency rather than CPU parallelism.
+const FETCH_WORKERS: usize = 8;
+
+/// Revisions per `call_batch` round-trip during the fetch phase. Keeps a
+/// single HTTP request from growing unboundedly on a very large sync while
+/// still cutting the request count roughly by this factor versus one request
+/// per revision.
+const FETCH_BATCH_SIZE: usize = 20;
+
+/// Our own app-level side table mapping a DokuWiki `(page/media, revision)` to
+/// the fast-import mark that was assigned to its blob, so a resumed import can
+/// reference unchanged content by mark (`M 100644 :N path`) instead of
+/// re-fetching and re-hashing it.
+///
+/// This is deliberately NOT the marks file `import_marks`/`export_marks` point
+/// to - that file is owned entirely by `git fast-import` itself (in its own
+/// `:N <sha1>` format, via the `feature import-marks=`/`export-marks=` lines
+/// below) so that a mark number we reuse here is actually backed by a real
+/// object in the new run. We persist this alongside it, at a derived path, to
+/// remember which revision each mark belongs to - something fast-import's
+/// marks file has no notion of.
+#[derive(Default, Serialize, Deserialize)]
+struct MarkTable {
+    next_mark: u64,
+    marks: HashMap<String, u64>,
+}
+
+/// Path of our JSON side table for a given fast-import marks path
+fn side_table_path(marks_path: &Path) -> PathBuf {
+    let mut name = marks_path.as_os_str().to_os_string();
+    name.push(".dokuwiki-marks.json");
+    PathBuf::from(name)
+}
+
+fn mark_key(is_media: bool, id: &str, version: i64) -> String {
+    format!("{}:{}:{}", if is_media { "media" } else { "page" }, id, version)
+}
+
+/// Read a single git config value, or `None` if unset/unavailable
+fn git_config(key: &str) -> Option<String> {
+    let output = std::process::Command::new("git").args(["config", key]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if value.is_empty() { None } else { Some(value) }
+}
+
+/// Whether page blobs should be translated from DokuWiki markup to Markdown
+/// on import. This binary has no CLI surface beyond the remote-helper
+/// protocol (git invokes it as `git-remote-dokuwiki <name> <url>` and speaks
+/// to it over stdin), so the conversion mode is a git-config toggle rather
+/// than a flag: `dokuwiki.importFormat` if set, otherwise falling back to
+/// the same `dokuwiki.format` switch `fast_export` uses for the push side,
+/// so a repo that sets just `dokuwiki.format=markdown` still round-trips.
+fn markdown_format_enabled() -> bool {
+    match git_config("dokuwiki.importFormat").as_deref() {
+        Some("markdown") => true,
+        Some(_) => false,
+        None => git_config("dokuwiki.format").as_deref() == Some("markdown"),
+    }
+}
+
+/// A git-cliff-style per-commit message template: a `{{var}}` placeholder
+/// string from `dokuwiki.commitTemplate`, rendered per timestamp group, then
+/// run through an ordered list of regex `(pattern, replacement)`
+/// postprocessors from `dokuwiki.commitPostprocess` (same `pattern=>replacement`
+/// shape as `dokuwiki.pathMap`). Lets a repo strip DokuWiki autosummary noise
+/// or inject ticket links without patching the crate. When no template is
+/// configured, `render` returns `None` and the caller keeps its built-in
+/// message format.
+struct MessageTemplate {
+    template: Option<String>,
+    postprocessors: Vec<(Regex, String)>,
+}
+
+fn load_message_template() -> MessageTemplate {
+    let template = git_config("dokuwiki.commitTemplate");
+
+    let postprocessors = git_config("dokuwiki.commitPostprocess")
+        .map(|raw| {
+            raw.split(',')
+                .filter_map(|entry| {
+                    let (pattern, replacement) = entry.trim().split_once("=>")?;
+                    let regex = Regex::new(pattern.trim()).ok()?;
+                    Some((regex, replacement.trim().to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    MessageTemplate { template, postprocessors }
+}
+
+impl MessageTemplate {
+    /// Render the template for one timestamp's group of revisions, or `None`
+    /// if `dokuwiki.commitTemplate` isn't set
+    fn render(&self, revs: &[&Revision], timestamp: i64, authors: &str) -> Option<String> {
+        let template = self.template.as_ref()?;
+
+        let ids: Vec<&str> = revs.iter().map(|r| r.id.as_str()).collect();
+        let summaries: Vec<String> = revs
+            .iter()
+            .filter_map(|r| if r.summary.is_empty() { None } else { Some(format!("{}: {}", r.id, r.summary)) })
+            .collect();
+
+        let mut revision_types: Vec<&str> = revs.iter().map(|r| r.revision_type.as_str()).collect();
+        revision_types.sort();
+        revision_types.dedup();
+        let revision_type = if revision_types.len() == 1 { revision_types[0].to_string() } else { "mixed".to_string() };
+
+        let is_media = if revs.iter().all(|r| r.is_media) {
+            "true"
+        } else if revs.iter().all(|r| !r.is_media) {
+            "false"
+        } else {
+            "mixed"
+        };
+
+        let mut rendered = template
+            .replace("{{ids}}", &ids.join(", "))
+            .replace("{{summaries}}", &summaries.join("\n"))
+            .replace("{{authors}}", authors)
+            .replace("{{timestamp}}", &timestamp.to_string())
+            .replace("{{revision_type}}", &revision_type)
+            .replace("{{count}}", &revs.len().to_string())
+            .replace("{{is_media}}", is_media);
+
+        for (pattern, replacement) in &self.postprocessors {
+            rendered = pattern.replace_all(&rendered, replacement.as_str()).to_string();
+        }
+
+        Some(rendered)
+    }
+}
+
+/// Mailmap-style author identity resolution: canonicalizes a DokuWiki login
+/// to a `(Name, email)` pair so that garbage-derived addresses like
+/// `john.doe@wiki.example.com` don't leak into the git history, and so that
+/// two logins belonging to the same person collapse into a single author
+/// when grouped into one commit below.
+///
+/// Loaded from a git-style mailmap file (default `.mailmap` at the repo
+/// root, override with `git config dokuwiki.mailmap <path>`) with lines of
+/// the form:
+///
+///     Proper Name <proper@email.xx> <dokuwiki-login>
+///
+/// DokuWiki logins aren't email addresses, so unlike a real git mailmap the
+/// second `<...>` field holds the wiki login being mapped rather than a
+/// commit email.
+struct Mailmap {
+    entries: HashMap<String, (String, String)>,
+}
+
+fn load_mailmap() -> Mailmap {
+    let path = git_config("dokuwiki.mailmap").unwrap_or_else(|| ".mailmap".to_string());
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Mailmap { entries: HashMap::new() };
+    };
+
+    let line_re = Regex::new(r"^(.+?)\s*<([^>]+)>\s*<([^>]+)>$").unwrap();
+    let entries = contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let caps = line_re.captures(line)?;
+            Some((caps[3].to_string(), (caps[1].trim().to_string(), caps[2].to_string())))
+        })
+        .collect();
+
+    Mailmap { entries }
+}
+
+impl Mailmap {
+    /// Resolve a DokuWiki login to its canonical `(name, email)`, or
+    /// `(login, "")` when unmapped so the caller can fall back to its
+    /// existing synthesized-email behavior
+    fn resolve(&self, login: &str) -> (String, String) {
+        self.entries.get(login).cloned().unwrap_or_else(|| (login.to_string(), String::new()))
+    }
+}
+
+fn load_marks(path: &Path) -> MarkTable {
+    fs::read(path)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_marks(path: &Path, table: &MarkTable) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_vec_pretty(table)?;
+    fs::write(path, data)?;
+    Ok(())
+}
+
 /// A revision to be imported (page or media)
 #[derive(Debug)]
 struct Revision {
@@ -18,6 +227,137 @@ struct Revision {
     is_media: bool,
 }
 
+/// One revision whose content needs to be fetched over the network
+struct FetchJob {
+    id: String,
+    version: i64,
+    is_media: bool,
+}
+
+/// On-disk cache of fetched revision content, keyed by the same `(is_media,
+/// id, version)` string as `mark_key`. DokuWiki revisions are content-addressed
+/// by an immutable timestamp, so a cache entry never needs to be invalidated:
+/// once a version has been fetched it can be reused forever, making a resumed
+/// or re-run import on a large wiki skip the fetch phase almost entirely.
+/// Disabled unless `git config dokuwiki.cacheDir <path>` is set.
+struct ContentCache {
+    dir: Option<std::path::PathBuf>,
+}
+
+fn load_content_cache() -> ContentCache {
+    ContentCache { dir: git_config("dokuwiki.cacheDir").map(std::path::PathBuf::from) }
+}
+
+impl ContentCache {
+    fn path_for(&self, key: &str) -> Option<std::path::PathBuf> {
+        Some(self.dir.as_ref()?.join(key.replace(':', "_")))
+    }
+
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.path_for(key)?).ok()
+    }
+
+    fn put(&self, key: &str, data: &[u8]) {
+        let Some(path) = self.path_for(key) else { return };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, data);
+    }
+}
+
+/// Fetch every job's content concurrently over a small pool of cloned
+/// connections, returning each result keyed by `mark_key`. Pages and media
+/// are each fetched `FETCH_BATCH_SIZE` at a time via `get_pages_batch`/
+/// `get_attachments_batch` rather than one `core.getPage`/`core.getMedia`
+/// call per job, so a large sync costs a fraction of the round-trips it used
+/// to. Fetching is still the only part of the import that's parallelized:
+/// the caller assigns blob/commit marks and writes the fast-import stream in
+/// a single serial pass afterward, in the existing deterministic
+/// (sorted-timestamp) order, so a reordered completion here can never
+/// change the emitted stream.
+fn fetch_contents(
+    client: &DokuWikiClient,
+    jobs: &[FetchJob],
+    cache: &ContentCache,
+    verbosity: Verbosity,
+) -> HashMap<String, Result<Vec<u8>, String>> {
+    if jobs.is_empty() {
+        return HashMap::new();
+    }
+
+    let results: Mutex<HashMap<String, Result<Vec<u8>, String>>> = Mutex::new(HashMap::new());
+
+    // Serve whatever's already on disk without a round-trip, and only batch
+    // up the jobs that actually need to hit the network.
+    let mut to_fetch: Vec<&FetchJob> = Vec::new();
+    for job in jobs {
+        let key = mark_key(job.is_media, &job.id, job.version);
+        match cache.get(&key) {
+            Some(cached) => {
+                results.lock().unwrap().insert(key, Ok(cached));
+            }
+            None => to_fetch.push(job),
+        }
+    }
+
+    if to_fetch.is_empty() {
+        return results.into_inner().unwrap();
+    }
+
+    let (media_jobs, page_jobs): (Vec<&FetchJob>, Vec<&FetchJob>) = to_fetch.into_iter().partition(|job| job.is_media);
+    let mut chunks: Vec<&[&FetchJob]> = Vec::new();
+    chunks.extend(page_jobs.chunks(FETCH_BATCH_SIZE));
+    chunks.extend(media_jobs.chunks(FETCH_BATCH_SIZE));
+
+    let worker_count = FETCH_WORKERS.min(chunks.len());
+    verbosity.debug(&format!(
+        "Fetching {} revision(s) in {} batch(es) with {} worker(s)",
+        page_jobs.len() + media_jobs.len(),
+        chunks.len(),
+        worker_count
+    ));
+
+    let queue: Mutex<VecDeque<&[&FetchJob]>> = Mutex::new(chunks.into_iter().collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..worker_count {
+            let queue = &queue;
+            let results = &results;
+            let mut worker_client = client.clone();
+            scope.spawn(move || loop {
+                let chunk = queue.lock().unwrap().pop_front();
+                let Some(chunk) = chunk else { break };
+                let is_media = chunk[0].is_media;
+
+                let ids: Vec<(&str, i64)> = chunk.iter().map(|job| (job.id.as_str(), job.version)).collect();
+                let fetched: Vec<Result<Vec<u8>>> = if is_media {
+                    match worker_client.get_attachments_batch(&ids) {
+                        Ok(batch) => batch,
+                        Err(e) => chunk.iter().map(|_| Err(anyhow::anyhow!(e.to_string()))).collect(),
+                    }
+                } else {
+                    match worker_client.get_pages_batch(&ids) {
+                        Ok(batch) => batch.into_iter().map(|r| r.map(String::into_bytes)).collect(),
+                        Err(e) => chunk.iter().map(|_| Err(anyhow::anyhow!(e.to_string()))).collect(),
+                    }
+                };
+
+                let mut out = results.lock().unwrap();
+                for (job, fetched) in chunk.iter().zip(fetched) {
+                    let key = mark_key(job.is_media, &job.id, job.version);
+                    if let Ok(ref data) = fetched {
+                        cache.put(&key, data);
+                    }
+                    out.insert(key, fetched.map_err(|e| e.to_string()));
+                }
+            });
+        }
+    });
+
+    results.into_inner().unwrap()
+}
+
 /// Convert a page ID to a file path
 fn page_id_to_path(page_id: &str, namespace: Option<&str>, extension: &str) -> String {
     let mut id = page_id.to_string();
@@ -53,11 +393,33 @@ fn media_id_to_path(media_id: &str, namespace: Option<&str>) -> String {
     parts.join("/")
 }
 
+/// Which extra refs, beyond the single `main` branch, should be emitted
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RefsConfig {
+    /// Emit a lightweight `dw-<timestamp>` tag for every imported commit; no
+    /// annotated tag objects are created
+    pub tags: bool,
+    /// Emit a `refs/heads/<namespace>` branch per top-level namespace
+    /// touched. Each branch is a pointer, not a filtered history: it's reset
+    /// to whichever commit on the single `main` timeline last touched that
+    /// namespace, and checking it out gives the full tree as of that commit
+    /// (every namespace's files), not just that namespace's pages. This
+    /// mirrors a release-tag-style "last touched here" bookmark rather than
+    /// `git subtree`/`filter-repo`-style isolated per-namespace history.
+    pub namespace_branches: bool,
+}
+
 /// Generate fast-import stream for wiki history
 /// If `since_timestamp` is provided, only generate commits newer than that timestamp
 /// If `parent_sha` is provided, use it as the parent for the first incremental commit
 /// If `depth` is provided, limit the number of revisions per page/media
+/// If `import_marks`/`export_marks` are provided, reuse blob marks recorded from a
+/// previous run instead of refetching content for a `(page, revision)` we've already seen
+/// If `dry_run` is set, stop after discovery (namespace filtering, depth truncation,
+/// timestamp grouping) and print the planned commit/revision counts to stderr instead of
+/// fetching any content or writing a fast-import stream to `out`
 /// Returns the latest revision timestamp that was imported, if any
+#[allow(clippy::too_many_arguments)]
 pub fn generate<W: Write>(
     client: &mut DokuWikiClient,
     namespace: Option<&str>,
@@ -66,9 +428,31 @@ pub fn generate<W: Write>(
     wiki_host: &str,
     extension: &str,
     depth: Option<u32>,
+    import_marks: Option<&Path>,
+    export_marks: Option<&Path>,
+    refs_config: RefsConfig,
+    dry_run: bool,
     verbosity: Verbosity,
     out: &mut W,
 ) -> Result<Option<i64>> {
+    // In markdown authoring mode, pages import as `.md` files with their
+    // content translated from DokuWiki syntax below, mirroring `fast_export`.
+    let format_markdown = markdown_format_enabled();
+    let extension = if format_markdown { "md" } else { extension };
+
+    // Optional `dokuwiki.commitTemplate`/`dokuwiki.commitPostprocess` override
+    // for how a timestamp group's commit message is built; falls back to the
+    // built-in format below when unset.
+    let message_template = load_message_template();
+
+    // Canonicalizes DokuWiki logins to real names/emails; falls back to the
+    // existing synthesized-email behavior for any login it doesn't cover.
+    let mailmap = load_mailmap();
+
+    // Our JSON side table lives next to whichever real marks path git gave us,
+    // not at that path itself - see `MarkTable`'s doc comment.
+    let mark_table_path = import_marks.or(export_marks).map(side_table_path);
+    let mut mark_table = mark_table_path.as_deref().map(load_marks).unwrap_or_default();
     let mut all_revisions: Vec<Revision> = Vec::new();
 
     // For incremental fetches, use getRecentChanges to find changed items,
@@ -120,7 +504,7 @@ pub fn generate<W: Write>(
                     }
                 }
                 Err(e) => {
-                    eprintln!("Warning: could not get history for {}: {}", page_id, e);
+                    eprintln!("Warning: could not get history for {}: {}", page_id, crate::redact::redact(&e.to_string()));
                 }
             }
         }
@@ -167,7 +551,7 @@ pub fn generate<W: Write>(
                     }
                 }
                 Err(e) => {
-                    eprintln!("Warning: could not get media history for {}: {}", media_id, e);
+                    eprintln!("Warning: could not get media history for {}: {}", media_id, crate::redact::redact(&e.to_string()));
                 }
             }
         }
@@ -214,7 +598,7 @@ pub fn generate<W: Write>(
                     }
                 }
                 Err(e) => {
-                    eprintln!("Warning: could not get history for {}: {}", page.id, e);
+                    eprintln!("Warning: could not get history for {}: {}", page.id, crate::redact::redact(&e.to_string()));
                     all_revisions.push(Revision {
                         id: page.id.clone(),
                         version: page.revision,
@@ -271,7 +655,7 @@ pub fn generate<W: Write>(
                     }
                 }
                 Err(e) => {
-                    eprintln!("Warning: could not get media history for {}: {}", media.id, e);
+                    eprintln!("Warning: could not get media history for {}: {}", media.id, crate::redact::redact(&e.to_string()));
                     // Fall back to current version
                     all_revisions.push(Revision {
                         id: media.id.clone(),
@@ -315,8 +699,67 @@ pub fn generate<W: Write>(
         return Ok(None);
     }
 
+    if dry_run {
+        // Counts come straight from `all_revisions`, which already reflects
+        // namespace filtering, depth truncation and the incremental
+        // `since_timestamp` cutoff, so the plan matches what a real run would do.
+        let mut counts_by_time: HashMap<i64, (usize, usize)> = HashMap::new();
+        for rev in &all_revisions {
+            let entry = counts_by_time.entry(rev.version).or_insert((0, 0));
+            if rev.revision_type == "D" {
+                entry.1 += 1;
+            } else {
+                entry.0 += 1;
+            }
+        }
+        let mut plan_timestamps: Vec<i64> = counts_by_time.keys().copied().collect();
+        plan_timestamps.sort();
+
+        eprintln!(
+            "Import plan: {} commit(s), {} revision(s)",
+            plan_timestamps.len(),
+            all_revisions.len()
+        );
+        for timestamp in &plan_timestamps {
+            let (adds, deletes) = counts_by_time[timestamp];
+            eprintln!("  {}: +{} file(s), -{} file(s)", timestamp, adds, deletes);
+        }
+
+        let latest = plan_timestamps.last().copied();
+        if let Some(ts) = latest {
+            eprintln!("Would-be latest timestamp: {}", ts);
+        }
+        return Ok(latest);
+    }
+
     verbosity.info("Generating git history...");
 
+    // Let `git fast-import` own the real marks file, in its own `:N <sha1>`
+    // format, rather than our JSON side table above: these `feature` lines
+    // must be the first thing fast-import sees so it loads prior marks before
+    // any `M 100644 :N path` below tries to reuse one. `-if-exists` tolerates
+    // there being no prior file yet, e.g. on the very first import.
+    if let Some(path) = import_marks {
+        writeln!(out, "feature import-marks-if-exists={}", path.display())?;
+    }
+    if let Some(path) = export_marks {
+        writeln!(out, "feature export-marks={}", path.display())?;
+    }
+
+    // Fetch every revision's content concurrently up front (skipping deletes,
+    // which carry no content, and anything a previous run already fetched and
+    // recorded in `mark_table`). The commit/blob marks assigned below still
+    // come entirely from the serial pass over `all_revisions` in timestamp
+    // order, so this can't perturb the emitted stream.
+    let fetch_jobs: Vec<FetchJob> = all_revisions
+        .iter()
+        .filter(|rev| rev.revision_type != "D")
+        .filter(|rev| !mark_table.marks.contains_key(&mark_key(rev.is_media, &rev.id, rev.version)))
+        .map(|rev| FetchJob { id: rev.id.clone(), version: rev.version, is_media: rev.is_media })
+        .collect();
+    let content_cache = load_content_cache();
+    let mut fetched_content = fetch_contents(client, &fetch_jobs, &content_cache, verbosity);
+
     // Group revisions by timestamp
     let mut revisions_by_time: HashMap<i64, Vec<&Revision>> = HashMap::new();
     for rev in &all_revisions {
@@ -326,8 +769,9 @@ pub fn generate<W: Write>(
     // Track current file contents
     let mut current_files: HashMap<String, Vec<u8>> = HashMap::new();
 
-    let mut mark: u64 = 1;
+    let mut mark: u64 = mark_table.next_mark.max(1);
     let mut last_commit_mark: Option<u64> = None;
+    let mut namespace_commit_marks: HashMap<String, u64> = HashMap::new();
     let mut commit_count = 0;
     let mut latest_timestamp: i64 = 0;
 
@@ -337,11 +781,13 @@ pub fn generate<W: Write>(
     for timestamp in timestamps {
         let revs = &revisions_by_time[&timestamp];
 
-        // Collect authors and summaries
-        let mut authors: Vec<&str> = revs.iter().map(|r| r.author.as_str()).collect();
-        authors.sort();
-        authors.dedup();
-        let author = authors.join(", ");
+        // Collect authors and summaries. Resolving through the mailmap first
+        // means two logins mapped to the same canonical identity collapse
+        // into one author instead of both showing up in the commit.
+        let mut identities: Vec<(String, String)> = revs.iter().map(|r| mailmap.resolve(&r.author)).collect();
+        identities.sort();
+        identities.dedup();
+        let author = identities.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ");
 
         let summaries: Vec<String> = revs
             .iter()
@@ -354,16 +800,18 @@ pub fn generate<W: Write>(
             })
             .collect();
 
-        let message = if summaries.is_empty() {
-            let ids: Vec<&str> = revs.iter().map(|r| r.id.as_str()).collect();
-            if ids.len() == 1 {
-                format!("Edit {}", ids[0])
+        let message = message_template.render(revs, timestamp, &author).unwrap_or_else(|| {
+            if summaries.is_empty() {
+                let ids: Vec<&str> = revs.iter().map(|r| r.id.as_str()).collect();
+                if ids.len() == 1 {
+                    format!("Edit {}", ids[0])
+                } else {
+                    format!("Edit {} items", ids.len())
+                }
             } else {
-                format!("Edit {} items", ids.len())
+                summaries.join("\n")
             }
-        } else {
-            summaries.join("\n")
-        };
+        });
 
         // Fetch content for each file at this revision
         let mut blobs: Vec<(String, u64)> = Vec::new();
@@ -383,11 +831,26 @@ pub fn generate<W: Write>(
                 continue;
             }
 
-            // Fetch content
-            let content_result = if rev.is_media {
-                client.get_attachment_version(&rev.id, rev.version)
-            } else {
-                client.get_page_version(&rev.id, rev.version).map(|s| s.into_bytes())
+            // Reuse the blob mark from a previous run if we've already imported
+            // this exact (page/media, revision) before, instead of refetching it
+            let key = mark_key(rev.is_media, &rev.id, rev.version);
+            if let Some(&blob_mark) = mark_table.marks.get(&key) {
+                blobs.push((path, blob_mark));
+                continue;
+            }
+
+            // Content was already fetched by the parallel phase above
+            let content_result = match fetched_content.remove(&key) {
+                Some(Ok(data)) => {
+                    if !rev.is_media && format_markdown {
+                        let text = String::from_utf8_lossy(&data).to_string();
+                        Ok(crate::markdown::from_dokuwiki(&text).into_bytes())
+                    } else {
+                        Ok(data)
+                    }
+                }
+                Some(Err(e)) => Err(anyhow::anyhow!(e)),
+                None => Err(anyhow::anyhow!("content for {}@{} was not fetched", rev.id, rev.version)),
             };
 
             match content_result {
@@ -402,10 +865,11 @@ pub fn generate<W: Write>(
                     writeln!(out)?;
 
                     current_files.insert(path.clone(), data);
+                    mark_table.marks.insert(key, blob_mark);
                     blobs.push((path, blob_mark));
                 }
                 Err(e) => {
-                    eprintln!("Warning: could not fetch {}@{}: {}", rev.id, rev.version, e);
+                    eprintln!("Warning: could not fetch {}@{}: {}", rev.id, rev.version, crate::redact::redact(&e.to_string()));
                 }
             }
         }
@@ -418,7 +882,11 @@ pub fn generate<W: Write>(
         let commit_mark = mark;
         mark += 1;
 
-        let email = format!("{}@{}", author.replace(' ', ".").replace(',', ""), wiki_host);
+        let email = if identities.len() == 1 && !identities[0].1.is_empty() {
+            identities[0].1.clone()
+        } else {
+            format!("{}@{}", author.replace(' ', ".").replace(',', ""), wiki_host)
+        };
 
         writeln!(out, "commit refs/dokuwiki/origin/heads/main")?;
         writeln!(out, "mark :{}", commit_mark)?;
@@ -446,6 +914,30 @@ pub fn generate<W: Write>(
 
         writeln!(out)?;
 
+        if refs_config.tags {
+            // fast-import's `tag` command always targets `refs/tags/<name>`, which
+            // isn't in our private tracking namespace and the declared refspec
+            // (`refs/tags/*:refs/dokuwiki/origin/tags/*`) doesn't rewrite stream
+            // output - it only tells git how to interpret refs we create ourselves.
+            // So, same as the namespace-branch refs below, point a lightweight ref
+            // directly at `refs/dokuwiki/origin/tags/<name>` via `reset`.
+            let tag_name = format!("dw-{}", timestamp);
+            writeln!(out, "reset refs/dokuwiki/origin/tags/{}", tag_name)?;
+            writeln!(out, "from :{}", commit_mark)?;
+        }
+
+        if refs_config.namespace_branches {
+            // Record the latest commit to touch each namespace; see
+            // `RefsConfig::namespace_branches`'s doc comment for why this is
+            // a bookmark into the shared `main` timeline (full tree at that
+            // commit) rather than a namespace-filtered history of its own.
+            for rev in revs {
+                if let Some((ns, _)) = rev.id.split_once(':') {
+                    namespace_commit_marks.insert(ns.to_string(), commit_mark);
+                }
+            }
+        }
+
         last_commit_mark = Some(commit_mark);
         commit_count += 1;
         latest_timestamp = latest_timestamp.max(timestamp);
@@ -455,7 +947,19 @@ pub fn generate<W: Write>(
         }
     }
 
+    if refs_config.namespace_branches {
+        for (ns, commit_mark) in &namespace_commit_marks {
+            writeln!(out, "reset refs/dokuwiki/origin/heads/{}", ns)?;
+            writeln!(out, "from :{}", commit_mark)?;
+        }
+    }
+
     verbosity.info(&format!("Generated {} commits", commit_count));
 
+    if let Some(path) = &mark_table_path {
+        mark_table.next_mark = mark;
+        save_marks(path, &mark_table)?;
+    }
+
     Ok(if latest_timestamp > 0 { Some(latest_timestamp) } else { None })
 }
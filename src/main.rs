@@ -10,7 +10,10 @@
 mod dokuwiki;
 mod fast_export;
 mod fast_import;
+mod markdown;
+mod merge;
 mod protocol;
+mod redact;
 mod verbosity;
 
 use verbosity::Verbosity;
@@ -46,11 +49,11 @@ fn main() -> Result<()> {
         std::process::exit(1);
     }
 
-    let _remote_name = &args[1];
+    let remote_name = &args[1];
     let url = &args[2];
     let verbosity = Verbosity::from_env();
 
-    let mut helper = RemoteHelper::new(url, verbosity)?;
+    let mut helper = RemoteHelper::new(remote_name, url, verbosity)?;
 
     let stdin = io::stdin();
     let mut stdin = stdin.lock();
@@ -110,16 +113,39 @@ struct RemoteHelper {
     imported: bool,
     verbosity: Verbosity,
     depth: Option<u32>,
+    import_marks: Option<String>,
+    export_marks: Option<String>,
+    tags_mode: bool,
+    namespace_branches: bool,
 }
 
 impl RemoteHelper {
-    fn new(url: &str, verbosity: Verbosity) -> Result<Self> {
-        let (wiki_url, user, namespace, extension) = parse_url(url)?;
+    fn new(remote_name: &str, url: &str, verbosity: Verbosity) -> Result<Self> {
+        let parsed = parse_url(url)?;
 
-        let mut client = DokuWikiClient::new(&wiki_url, &user, verbosity)?;
+        let mut client = DokuWikiClient::new(&parsed.wiki_url, &parsed.user, parsed.namespace.as_deref(), verbosity)?;
         client.ensure_authenticated()?;
 
-        Ok(Self { client, namespace, extension, imported: false, verbosity, depth: None })
+        // The `import-marks`/`export-marks` capabilities below carry the marks
+        // file path inline, rather than leaving it to be set via an "option"
+        // command - there is no such option in git's remote-helper protocol,
+        // so unlike `depth`/`verbosity` this one has to be decided by us. Use
+        // the same `.git/`-backed, per-remote scheme as the auth cookie jar
+        // in `dokuwiki.rs` so two dokuwiki remotes in one repo don't collide.
+        let marks_path = default_marks_path(remote_name).map(|p| p.to_string_lossy().into_owned());
+
+        Ok(Self {
+            client,
+            namespace: parsed.namespace,
+            extension: parsed.extension,
+            imported: false,
+            verbosity,
+            depth: None,
+            import_marks: marks_path.clone(),
+            export_marks: marks_path,
+            tags_mode: parsed.tags_mode,
+            namespace_branches: parsed.namespace_branches,
+        })
     }
 
     fn capabilities<W: Write>(&self, out: &mut W) -> Result<()> {
@@ -127,6 +153,15 @@ impl RemoteHelper {
         writeln!(out, "export")?;
         writeln!(out, "option")?;
         writeln!(out, "refspec refs/heads/*:refs/dokuwiki/origin/heads/*")?;
+        if self.tags_mode {
+            writeln!(out, "refspec refs/tags/*:refs/dokuwiki/origin/tags/*")?;
+        }
+        if let Some(path) = &self.import_marks {
+            writeln!(out, "*import-marks={}", path)?;
+        }
+        if let Some(path) = &self.export_marks {
+            writeln!(out, "*export-marks={}", path)?;
+        }
         writeln!(out)?;
         Ok(())
     }
@@ -146,6 +181,14 @@ impl RemoteHelper {
                 }
                 writeln!(out, "ok")?;
             }
+            "import-marks" => {
+                self.import_marks = Some(value.to_string());
+                writeln!(out, "ok")?;
+            }
+            "export-marks" => {
+                self.export_marks = Some(value.to_string());
+                writeln!(out, "ok")?;
+            }
             _ => {
                 // Unsupported option
                 writeln!(out, "unsupported")?;
@@ -178,10 +221,73 @@ impl RemoteHelper {
             writeln!(out, "@refs/heads/main HEAD")?;
             writeln!(out, "? refs/heads/main")?;
         }
+
+        if self.namespace_branches {
+            for ns in self.discover_top_level_namespaces().unwrap_or_default() {
+                let ref_name = format!("refs/heads/{}", ns);
+                if has_new_changes {
+                    writeln!(out, "? {}", ref_name)?;
+                } else if let Some(sha) = self.get_ref_sha(&ref_name) {
+                    writeln!(out, "{} {}", sha, ref_name)?;
+                } else {
+                    writeln!(out, "? {}", ref_name)?;
+                }
+            }
+        }
+
+        if self.tags_mode {
+            if has_new_changes {
+                // Tag names are per-revision (`dw-<timestamp>`) and only known once a
+                // revision has actually been imported, so there's nothing concrete to
+                // advertise yet. The forced `refs/heads/main` import above creates the
+                // real `refs/dokuwiki/origin/tags/*` refs as a side effect; a later
+                // `list` call (once `has_new_changes` is false) enumerates them below.
+            } else {
+                for tag_ref in self.discover_tag_refs().unwrap_or_default() {
+                    let ref_name = format!("refs/tags/{}", tag_ref);
+                    if let Some(sha) = self.get_ref_sha(&ref_name) {
+                        writeln!(out, "{} {}", sha, ref_name)?;
+                    }
+                }
+            }
+        }
+
         writeln!(out)?;
         Ok(())
     }
 
+    /// Discover the top-level DokuWiki namespaces (the first `:`-delimited segment
+    /// of each page id) so each can be surfaced as its own git branch
+    fn discover_top_level_namespaces(&mut self) -> Result<Vec<String>> {
+        let pages = self.client.get_all_pages()?;
+        let mut namespaces: Vec<String> = pages
+            .iter()
+            .filter_map(|p| p.id.split_once(':').map(|(ns, _)| ns.to_string()))
+            .collect();
+        namespaces.sort();
+        namespaces.dedup();
+        Ok(namespaces)
+    }
+
+    /// Enumerate already-imported per-revision tag names from our local tracking
+    /// namespace (`refs/dokuwiki/origin/tags/*`). Unlike namespace branches, tag
+    /// names can't be discovered from the wiki API up front - they're only known
+    /// once `generate()` has actually walked revision history and created them -
+    /// so this reads back what a previous import already wrote to disk.
+    fn discover_tag_refs(&self) -> Result<Vec<String>> {
+        let output = ProcessCommand::new("git")
+            .args(["for-each-ref", "--format=%(refname)", "refs/dokuwiki/origin/tags/"])
+            .output()
+            .context("Failed to list tag refs")?;
+
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().filter_map(|line| line.strip_prefix("refs/dokuwiki/origin/tags/")).map(str::to_string).collect())
+    }
+
     fn import<W: Write>(&mut self, _ref_name: &str, out: &mut W) -> Result<()> {
         if self.imported {
             return Ok(());
@@ -192,11 +298,32 @@ impl RemoteHelper {
         let parent_sha = self.get_main_sha();
 
         let wiki_host = self.client.wiki_host().to_string();
-        let latest_revision = fast_import::generate(&mut self.client, self.namespace.as_deref(), since_timestamp, parent_sha.as_deref(), &wiki_host, &self.extension, self.depth, self.verbosity, out)?;
-
-        // Store the latest revision timestamp for future incremental fetches
-        if let Some(ts) = latest_revision {
-            self.set_latest_revision_timestamp(ts);
+        let import_marks = self.import_marks.as_deref().map(std::path::Path::new);
+        let export_marks = self.export_marks.as_deref().map(std::path::Path::new);
+        let dry_run = self.dry_run_enabled();
+        let latest_revision = fast_import::generate(
+            &mut self.client,
+            self.namespace.as_deref(),
+            since_timestamp,
+            parent_sha.as_deref(),
+            &wiki_host,
+            &self.extension,
+            self.depth,
+            import_marks,
+            export_marks,
+            fast_import::RefsConfig { tags: self.tags_mode, namespace_branches: self.namespace_branches },
+            dry_run,
+            self.verbosity,
+            out,
+        )?;
+
+        // In dry-run mode nothing was actually imported, so don't advance our
+        // incremental-fetch bookkeeping - a real import must still see the
+        // same `since_timestamp` afterward.
+        if !dry_run {
+            if let Some(ts) = latest_revision {
+                self.set_latest_revision_timestamp(ts);
+            }
         }
 
         self.imported = true;
@@ -204,6 +331,16 @@ impl RemoteHelper {
         Ok(())
     }
 
+    /// Whether `git config dokuwiki.dryRun` requests a planning-only import:
+    /// report the commit/revision count git would generate without fetching
+    /// any content or writing a fast-import stream. Intended for inspecting a
+    /// namespace/depth filter or an incremental cutoff out of band, not for
+    /// driving an actual `git fetch`/`git clone`.
+    fn dry_run_enabled(&self) -> bool {
+        let output = ProcessCommand::new("git").args(["config", "--bool", "dokuwiki.dryRun"]).output();
+        matches!(output, Ok(o) if o.status.success() && String::from_utf8_lossy(&o.stdout).trim() == "true")
+    }
+
     /// Get the timestamp of the latest imported revision
     /// We store this in git config since the wiki's lastModified field is unreliable
     fn get_latest_commit_timestamp(&self) -> Option<i64> {
@@ -229,8 +366,14 @@ impl RemoteHelper {
 
     /// Get the SHA of the current main branch tip, if any
     fn get_main_sha(&self) -> Option<String> {
+        self.get_ref_sha("refs/heads/main")
+    }
+
+    /// Get the SHA the given ref resolves to under our `refs/dokuwiki/origin/*` namespace, if any
+    fn get_ref_sha(&self, ref_name: &str) -> Option<String> {
+        let tracking_ref = ref_name.replacen("refs/", "refs/dokuwiki/origin/", 1);
         let output = ProcessCommand::new("git")
-            .args(["rev-parse", "refs/dokuwiki/origin/heads/main"])
+            .args(["rev-parse", &tracking_ref])
             .output()
             .ok()?;
 
@@ -258,31 +401,58 @@ impl RemoteHelper {
     }
 }
 
+/// Default on-disk path for git fast-import's marks file for this remote,
+/// under the repo's `.git` directory (same `git rev-parse --git-dir`
+/// approach `dokuwiki::get_repo_cookie_path` uses for the auth cookie jar),
+/// named after `remote_name` so more than one dokuwiki remote in one repo
+/// doesn't share a marks file. Returns `None` if the git dir can't be found,
+/// in which case the caller just doesn't advertise the marks capabilities.
+fn default_marks_path(remote_name: &str) -> Option<std::path::PathBuf> {
+    let output = ProcessCommand::new("git").args(["rev-parse", "--git-dir"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let git_dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(std::path::PathBuf::from(git_dir).join("dokuwiki").join(format!("{}.marks", remote_name)))
+}
+
 /// Default file extension for wiki pages
 const DEFAULT_EXTENSION: &str = "md";
 
-/// Parse a dokuwiki URL like `dokuwiki::user@host/namespace?ext=txt`
-/// Returns (wiki_url, user, namespace, extension)
-fn parse_url(url: &str) -> Result<(String, String, Option<String>, String)> {
+/// Result of parsing a `dokuwiki::user@host/namespace?ext=txt` URL
+struct ParsedUrl {
+    wiki_url: String,
+    user: String,
+    namespace: Option<String>,
+    extension: String,
+    /// `?refs=tags`: surface each imported commit as a lightweight `dw-<timestamp>` tag (not an annotated tag)
+    tags_mode: bool,
+    /// `?refs=namespaces`: surface each top-level namespace as its own branch
+    namespace_branches: bool,
+}
+
+/// Parse a dokuwiki URL like `dokuwiki::user@host/namespace?ext=txt&refs=tags`
+fn parse_url(url: &str) -> Result<ParsedUrl> {
     // Remove dokuwiki:: prefix if present
     let url = url.strip_prefix("dokuwiki::").unwrap_or(url);
 
     // Extract query parameters (e.g., ?ext=txt)
-    let (url, extension) = if let Some(query_pos) = url.find('?') {
+    let (url, extension, tags_mode, namespace_branches) = if let Some(query_pos) = url.find('?') {
         let query = &url[query_pos + 1..];
         let url = &url[..query_pos];
 
-        // Parse ext parameter
         let ext = query
             .split('&')
-            .find_map(|param| {
-                param.strip_prefix("ext=")
-            })
+            .find_map(|param| param.strip_prefix("ext="))
             .unwrap_or(DEFAULT_EXTENSION);
 
-        (url, ext.to_string())
+        let refs = query.split('&').find_map(|param| param.strip_prefix("refs="));
+        let tags_mode = refs == Some("tags");
+        let namespace_branches = refs == Some("namespaces");
+
+        (url, ext.to_string(), tags_mode, namespace_branches)
     } else {
-        (url, DEFAULT_EXTENSION.to_string())
+        (url, DEFAULT_EXTENSION.to_string(), false, false)
     };
 
     // Parse user@host/path
@@ -314,5 +484,5 @@ fn parse_url(url: &str) -> Result<(String, String, Option<String>, String)> {
     };
     let wiki_url = format!("{}://{}", protocol, host);
 
-    Ok((wiki_url, user, namespace, extension))
+    Ok(ParsedUrl { wiki_url, user, namespace, extension, tags_mode, namespace_branches })
 }
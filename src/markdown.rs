@@ -0,0 +1,224 @@
+//! Conversion between CommonMark and DokuWiki wiki markup
+//!
+//! Backs the `dokuwiki.format=markdown` authoring mode: with it set, pages are
+//! written and read as ordinary `.md` files in git, and [`to_dokuwiki`] /
+//! [`from_dokuwiki`] translate content at the git/wiki boundary (push and
+//! import respectively) so the wiki itself still stores native DokuWiki
+//! syntax, same as if it had been edited there directly.
+//!
+//! # Supported subset
+//!
+//! | Markdown | DokuWiki |
+//! |---|---|
+//! | `# .. #####` headings (`######` also becomes `==`) | `====== .. ==` headings |
+//! | `**bold**` | `**bold**` |
+//! | `*italic*` / `_italic_` | `//italic//` |
+//! | `` `code` `` | `''code''` |
+//! | fenced code block (with language) | `<code lang>...</code>` |
+//! | `[text](target)` | `[[target\|text]]` |
+//! | `![alt](path)` | `{{:path\|alt}}` |
+//! | `- item` / `1. item` | `  * item` / `  - item` |
+//! | `---` (thematic break) | `----` |
+//! | trailing hard break | `\\` |
+//!
+//! Anything outside this subset (tables, footnotes, raw HTML, nested
+//! blockquotes, ...) is passed through as literal text rather than dropped,
+//! so a round-trip never silently loses content — it just stops normalizing
+//! that particular construct.
+
+use comrak::nodes::{AstNode, ListType, NodeValue};
+use comrak::{parse_document, Arena, ComrakOptions};
+use regex::Regex;
+
+/// Render CommonMark `markdown` as DokuWiki wiki markup
+pub fn to_dokuwiki(markdown: &str) -> String {
+    let arena = Arena::new();
+    let options = ComrakOptions::default();
+    let root = parse_document(&arena, markdown, &options);
+
+    let mut out = String::new();
+    render_children(root, &mut out);
+    out.trim_end_matches('\n').to_string() + "\n"
+}
+
+fn render_children<'a>(node: &'a AstNode<'a>, out: &mut String) {
+    for child in node.children() {
+        render_node(child, out);
+    }
+}
+
+fn render_node<'a>(node: &'a AstNode<'a>, out: &mut String) {
+    let value = node.data.borrow().value.clone();
+    match value {
+        NodeValue::Document => render_children(node, out),
+        NodeValue::Paragraph => {
+            render_children(node, out);
+            out.push_str("\n\n");
+        }
+        NodeValue::Heading(heading) => {
+            let level = (7u8.saturating_sub(heading.level)).max(2);
+            let marker = "=".repeat(level as usize);
+            out.push_str(&marker);
+            out.push(' ');
+            render_children(node, out);
+            out.push(' ');
+            out.push_str(&marker);
+            out.push_str("\n\n");
+        }
+        NodeValue::Text(text) => out.push_str(&text),
+        NodeValue::Strong => {
+            out.push_str("**");
+            render_children(node, out);
+            out.push_str("**");
+        }
+        NodeValue::Emph => {
+            out.push_str("//");
+            render_children(node, out);
+            out.push_str("//");
+        }
+        NodeValue::Code(code) => {
+            out.push_str("''");
+            out.push_str(&code.literal);
+            out.push_str("''");
+        }
+        NodeValue::CodeBlock(block) => {
+            out.push_str("<code");
+            if !block.info.is_empty() {
+                out.push(' ');
+                out.push_str(&block.info);
+            }
+            out.push_str(">\n");
+            out.push_str(&block.literal);
+            out.push_str("</code>\n\n");
+        }
+        NodeValue::Link(link) => {
+            let mut text = String::new();
+            render_children(node, &mut text);
+            if text.is_empty() || text == link.url {
+                out.push_str(&format!("[[{}]]", link.url));
+            } else {
+                out.push_str(&format!("[[{}|{}]]", link.url, text));
+            }
+        }
+        NodeValue::Image(link) => {
+            let path = link.url.replace('/', ":");
+            let mut alt = String::new();
+            render_children(node, &mut alt);
+            if alt.is_empty() {
+                out.push_str(&format!("{{{{:{}}}}}", path));
+            } else {
+                out.push_str(&format!("{{{{:{}|{}}}}}", path, alt));
+            }
+        }
+        NodeValue::List(list) => {
+            render_list(node, out, list.list_type, 0);
+        }
+        NodeValue::BlockQuote => {
+            let mut inner = String::new();
+            render_children(node, &mut inner);
+            for line in inner.trim_end_matches('\n').lines() {
+                out.push_str("> ");
+                out.push_str(line);
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+        NodeValue::ThematicBreak => out.push_str("----\n\n"),
+        NodeValue::SoftBreak => out.push(' '),
+        NodeValue::LineBreak => out.push_str("\\\\\n"),
+        NodeValue::HtmlBlock(html) => {
+            out.push_str(&html.literal);
+            out.push('\n');
+        }
+        NodeValue::HtmlInline(html) => out.push_str(&html),
+        // Anything else (tables, footnotes, task-list markers, ...) isn't
+        // mapped to a DokuWiki equivalent; render its children verbatim so
+        // the text survives rather than vanishing.
+        _ => render_children(node, out),
+    }
+}
+
+/// Render a list's items, indenting two spaces per nesting level the way
+/// DokuWiki's own list syntax does
+fn render_list<'a>(node: &'a AstNode<'a>, out: &mut String, list_type: ListType, depth: usize) {
+    let indent = "  ".repeat(depth + 1);
+    let marker = match list_type {
+        ListType::Bullet => "*",
+        ListType::Ordered => "-",
+    };
+
+    for item in node.children() {
+        let is_nested_list = matches!(item.data.borrow().value, NodeValue::List(_));
+        if is_nested_list {
+            let NodeValue::List(inner) = item.data.borrow().value.clone() else { unreachable!() };
+            render_list(item, out, inner.list_type, depth + 1);
+            continue;
+        }
+
+        out.push_str(&indent);
+        out.push_str(marker);
+        out.push(' ');
+
+        let mut text = String::new();
+        for child in item.children() {
+            if matches!(child.data.borrow().value, NodeValue::List(_)) {
+                out.push_str(text.trim_end_matches('\n'));
+                out.push('\n');
+                text.clear();
+                let NodeValue::List(inner) = child.data.borrow().value.clone() else { unreachable!() };
+                render_list(child, out, inner.list_type, depth + 1);
+            } else {
+                render_node(child, &mut text);
+            }
+        }
+        out.push_str(text.trim_end_matches('\n'));
+        out.push('\n');
+    }
+}
+
+/// Render DokuWiki wiki markup back to CommonMark, the inverse of
+/// [`to_dokuwiki`] for the subset documented on this module, except lists:
+/// they are left in DokuWiki's `  * ` / `  - ` form, which CommonMark reads
+/// as bullet lists, so an ordered list comes back bulleted. Constructs
+/// outside that subset pass through unchanged.
+pub fn from_dokuwiki(wiki: &str) -> String {
+    let heading = Regex::new(r"(?m)^(={2,6})\s*(.+?)\s*=+\s*$").unwrap();
+    let code_block = Regex::new(r"(?s)<code *([^>\n]*)>\n?(.*?)</code>").unwrap();
+    let image = Regex::new(r"\{\{:([^|}]+)\|?([^}]*)\}\}").unwrap();
+    let link = Regex::new(r"\[\[([^|\]]+)\|?([^\]]*)\]\]").unwrap();
+    let mono = Regex::new(r"''([^'\n]+)''").unwrap();
+    let italic = Regex::new(r"(^|[^:])//([^/\n]+?)//").unwrap();
+    let hard_break = Regex::new(r"\\\\ *\n").unwrap();
+
+    let text = heading.replace_all(wiki, |caps: &regex::Captures| {
+        let level = (7u32.saturating_sub(caps[1].len() as u32)).clamp(1, 6);
+        format!("{} {}", "#".repeat(level as usize), caps[2].trim())
+    });
+
+    let text = code_block.replace_all(&text, |caps: &regex::Captures| {
+        format!("```{}\n{}```", caps[1].trim(), &caps[2])
+    });
+
+    let text = image.replace_all(&text, |caps: &regex::Captures| {
+        let path = caps[1].replace(':', "/");
+        let path = path.strip_prefix('/').unwrap_or(&path);
+        format!("![{}]({})", &caps[2], path)
+    });
+
+    let text = link.replace_all(&text, |caps: &regex::Captures| {
+        let target = caps[1].trim();
+        let label = caps[2].trim();
+        if label.is_empty() {
+            format!("[{}]({})", target, target)
+        } else {
+            format!("[{}]({})", label, target)
+        }
+    });
+
+    let text = mono.replace_all(&text, "`$1`");
+    let text = italic.replace_all(&text, "$1*$2*");
+    let text = hard_break.replace_all(&text, "  \n");
+    let text = text.replace("----", "---");
+
+    text.trim_end_matches('\n').to_string() + "\n"
+}
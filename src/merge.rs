@@ -0,0 +1,208 @@
+//! Line-based three-way merge, used to reconcile a page edited both locally
+//! and on the wiki since the last import instead of forcing a manual
+//! fetch/pull for every push.
+//!
+//! This is a minimal diff3: an LCS-based line diff of base->local and
+//! base->remote, each collapsed into hunks, which are then walked in
+//! base order. Hunks that touch disjoint regions of the base text are
+//! applied independently; hunks that overlap are left as a conflict with
+//! standard `<<<<<<<`/`=======`/`>>>>>>>` markers.
+
+/// Result of merging `local` and `remote` against their common `base`
+pub enum MergeResult {
+    /// No hunk from `local` overlapped a hunk from `remote`; this is the merged text
+    Clean(String),
+    /// At least one hunk overlapped; this is the merged text with conflict markers
+    /// around the colliding local/remote hunks, for the user to resolve by hand
+    Conflict(String),
+}
+
+/// A contiguous region of `base` (by line index, end-exclusive) that one side
+/// replaced with `lines` (possibly empty, for a pure deletion, or inserted
+/// with `base_start == base_end` for a pure insertion)
+struct Hunk {
+    base_start: usize,
+    base_end: usize,
+    lines: Vec<String>,
+}
+
+enum Op {
+    Equal,
+    Delete,
+    Insert(String),
+}
+
+/// Backtrack a standard LCS table into a sequence of equal/delete/insert ops
+/// turning `base` into `other`
+fn lcs_ops(base: &[&str], other: &[&str]) -> Vec<Op> {
+    let n = base.len();
+    let m = other.len();
+    let mut dp = vec![vec![0u32; m + 1]; n + 1];
+    for i in 1..=n {
+        for j in 1..=m {
+            dp[i][j] = if base[i - 1] == other[j - 1] {
+                dp[i - 1][j - 1] + 1
+            } else {
+                dp[i - 1][j].max(dp[i][j - 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (n, m);
+    while i > 0 && j > 0 {
+        if base[i - 1] == other[j - 1] {
+            ops.push(Op::Equal);
+            i -= 1;
+            j -= 1;
+        } else if dp[i - 1][j] >= dp[i][j - 1] {
+            ops.push(Op::Delete);
+            i -= 1;
+        } else {
+            ops.push(Op::Insert(other[j - 1].to_string()));
+            j -= 1;
+        }
+    }
+    while i > 0 {
+        ops.push(Op::Delete);
+        i -= 1;
+    }
+    while j > 0 {
+        ops.push(Op::Insert(other[j - 1].to_string()));
+        j -= 1;
+    }
+    ops.reverse();
+    ops
+}
+
+/// Diff `base` against `other`, collapsed into hunks positioned against `base`
+fn diff_hunks(base: &[&str], other: &[&str]) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    let mut ai = 0usize;
+    let mut current: Option<(usize, Vec<String>)> = None;
+
+    for op in lcs_ops(base, other) {
+        match op {
+            Op::Equal => {
+                if let Some((start, lines)) = current.take() {
+                    hunks.push(Hunk { base_start: start, base_end: ai, lines });
+                }
+                ai += 1;
+            }
+            Op::Delete => {
+                current.get_or_insert_with(|| (ai, Vec::new()));
+                ai += 1;
+            }
+            Op::Insert(line) => {
+                current.get_or_insert_with(|| (ai, Vec::new())).1.push(line);
+            }
+        }
+    }
+    if let Some((start, lines)) = current.take() {
+        hunks.push(Hunk { base_start: start, base_end: ai, lines });
+    }
+    hunks
+}
+
+fn ranges_overlap(a: &Hunk, b: &Hunk) -> bool {
+    a.base_start < b.base_end && b.base_start < a.base_end
+}
+
+/// Merge `local` and `remote`, both derived from `base`, into one text
+pub fn merge3(base: &str, local: &str, remote: &str) -> MergeResult {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let local_lines: Vec<&str> = local.lines().collect();
+    let remote_lines: Vec<&str> = remote.lines().collect();
+
+    let local_hunks = diff_hunks(&base_lines, &local_lines);
+    let remote_hunks = diff_hunks(&base_lines, &remote_lines);
+
+    let mut out: Vec<String> = Vec::new();
+    let mut conflict = false;
+    let mut pos = 0usize;
+    let (mut li, mut ri) = (0usize, 0usize);
+
+    // No-op (rather than slicing with a start past `end`) when a conflict
+    // emitted below has already advanced `pos` past the requested point -
+    // see the skip check at the top of the loop for why that can happen.
+    let emit_base = |out: &mut Vec<String>, pos: &mut usize, end: usize| {
+        if end <= *pos {
+            return;
+        }
+        for line in &base_lines[*pos..end] {
+            out.push((*line).to_string());
+        }
+        *pos = end;
+    };
+
+    while li < local_hunks.len() || ri < remote_hunks.len() {
+        // A hunk's base range can be fully swallowed by a conflict emitted
+        // for an earlier, overlapping hunk on the other side (e.g. two
+        // remote hunks both overlap the same local hunk, or chained
+        // overlaps on either side) - skip it outright instead of
+        // re-processing it against a `pos` that's already past its start.
+        // A pure insertion exactly at `pos` isn't swallowed and still applies.
+        if let Some(lh) = local_hunks.get(li) {
+            if lh.base_end <= pos && lh.base_start < pos {
+                li += 1;
+                continue;
+            }
+        }
+        if let Some(rh) = remote_hunks.get(ri) {
+            if rh.base_end <= pos && rh.base_start < pos {
+                ri += 1;
+                continue;
+            }
+        }
+
+        match (local_hunks.get(li), remote_hunks.get(ri)) {
+            (Some(lh), Some(rh)) if ranges_overlap(lh, rh) => {
+                let start = lh.base_start.min(rh.base_start);
+                let end = lh.base_end.max(rh.base_end);
+                emit_base(&mut out, &mut pos, start);
+                out.push("<<<<<<< local".to_string());
+                out.extend(lh.lines.clone());
+                out.push("=======".to_string());
+                out.extend(rh.lines.clone());
+                out.push(">>>>>>> remote".to_string());
+                conflict = true;
+                pos = end;
+                li += 1;
+                ri += 1;
+            }
+            (Some(lh), Some(rh)) if lh.base_start <= rh.base_start => {
+                emit_base(&mut out, &mut pos, lh.base_start);
+                out.extend(lh.lines.clone());
+                pos = lh.base_end;
+                li += 1;
+            }
+            (Some(_), Some(rh)) => {
+                emit_base(&mut out, &mut pos, rh.base_start);
+                out.extend(rh.lines.clone());
+                pos = rh.base_end;
+                ri += 1;
+            }
+            (Some(lh), None) => {
+                emit_base(&mut out, &mut pos, lh.base_start);
+                out.extend(lh.lines.clone());
+                pos = lh.base_end;
+                li += 1;
+            }
+            (None, Some(rh)) => {
+                emit_base(&mut out, &mut pos, rh.base_start);
+                out.extend(rh.lines.clone());
+                pos = rh.base_end;
+                ri += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+    emit_base(&mut out, &mut pos, base_lines.len());
+
+    let merged = out.join("\n") + "\n";
+    if conflict {
+        MergeResult::Conflict(merged)
+    } else {
+        MergeResult::Clean(merged)
+    }
+}
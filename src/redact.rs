@@ -0,0 +1,35 @@
+//! Secret redaction for verbose/debug output
+//!
+//! `-vv` logging prints URLs and subprocess argument lists verbatim, which can
+//! leak a password embedded in a `dokuwiki::user:pass@host` URL or sent over
+//! in a JSON-RPC request body. Callers register known secrets as they learn them (e.g. right
+//! after authenticating) and every diagnostic string is passed through
+//! [`redact`] before it reaches stderr, so enabling debug logging to diagnose
+//! a sync problem stays safe to paste into a bug report.
+
+use std::sync::RwLock;
+
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Register a secret string to be masked out of all future log output
+pub fn register_secret(secret: &str) {
+    if secret.is_empty() {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap();
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+    }
+}
+
+/// Replace every occurrence of a registered secret with `****`
+pub fn redact(text: &str) -> String {
+    let secrets = SECRETS.read().unwrap();
+    let mut result = text.to_string();
+    for secret in secrets.iter() {
+        if !secret.is_empty() {
+            result = result.replace(secret.as_str(), "****");
+        }
+    }
+    result
+}
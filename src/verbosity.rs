@@ -3,6 +3,8 @@
 use std::env;
 use std::sync::atomic::{AtomicU8, Ordering};
 
+use crate::redact::redact;
+
 /// Global verbosity level (can be updated by git's option command)
 static VERBOSITY_LEVEL: AtomicU8 = AtomicU8::new(0);
 
@@ -49,14 +51,14 @@ impl Verbosity {
     /// Print an info message (verbosity >= 2, i.e. git -v)
     pub fn info(&self, msg: &str) {
         if self.level() >= 2 {
-            eprintln!("{}", msg);
+            eprintln!("{}", redact(msg));
         }
     }
 
     /// Print a debug message (verbosity >= 3, i.e. git -vv)
     pub fn debug(&self, msg: &str) {
         if self.level() >= 3 {
-            eprintln!("DEBUG: {}", msg);
+            eprintln!("DEBUG: {}", redact(msg));
         }
     }
 }